@@ -0,0 +1,117 @@
+//! Compares drawing a frame of primitives through their lazy iterators
+//! with collecting every shape into a `Vec<Pixel>` first, like the primitives used to,
+//! and with filling the filled shapes a span at a time.
+//!
+//! Run with `cargo bench --bench primitives`.
+
+use std::time::Instant;
+
+use pixel_game_engine::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+    primitives::{Circle, Line, Rectangle, Span},
+};
+
+const FRAMES: u32 = 200;
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Collected,
+    Lazy,
+    Spans,
+}
+
+/// Writes the pixels to a frame buffer, the same way `Buffer::draw` does
+fn draw(frame: &mut [u8], pixels: impl IntoIterator<Item = Pixel>) {
+    for pixel in pixels {
+        let index = ((WIDTH * pixel.y() + pixel.x()) * 4) as usize;
+        if let Some(raw_pixel) = frame.get_mut(index..index + 4) {
+            raw_pixel.copy_from_slice(&WHITE);
+        }
+    }
+}
+
+/// Writes whole row slices to a frame buffer, the same way `Buffer::fill` does
+fn fill(frame: &mut [u8], spans: impl IntoIterator<Item = Span>) {
+    for span in spans {
+        let end = span.end.min(WIDTH);
+        if span.y >= HEIGHT || span.start >= end {
+            continue;
+        }
+
+        let start_index = ((WIDTH * span.y + span.start) * 4) as usize;
+        let end_index = ((WIDTH * span.y + end) * 4) as usize;
+        for raw_pixel in frame[start_index..end_index].chunks_exact_mut(4) {
+            raw_pixel.copy_from_slice(&WHITE);
+        }
+    }
+}
+
+fn shape(frame: &mut [u8], drawable: impl Drawable, mode: Mode) {
+    if mode == Mode::Collected {
+        let pixels: Vec<Pixel> = drawable.pixels().into_iter().collect();
+        draw(frame, pixels);
+    } else {
+        draw(frame, drawable.pixels());
+    }
+}
+
+fn filled_shape(frame: &mut [u8], drawable: impl Drawable + SpanDrawable, mode: Mode) {
+    if mode == Mode::Spans {
+        fill(frame, drawable.spans());
+    } else {
+        shape(frame, drawable, mode);
+    }
+}
+
+/// Draws one frame of shapes
+fn draw_frame(frame: &mut [u8], mode: Mode) {
+    for i in 0..50u32 {
+        let center = Pixel((100 + i * 12, 300));
+        shape(frame, Circle::new(center, 10 + i).hollow(), mode);
+        filled_shape(frame, Circle::new(center, 5 + i / 2).filled(), mode);
+        filled_shape(
+            frame,
+            Rectangle::new(Pixel((i * 10, i * 5)), 40, 30).filled(),
+            mode,
+        );
+        shape(
+            frame,
+            Rectangle::new(Pixel((i * 10, i * 5)), 60, 50).hollow(),
+            mode,
+        );
+        shape(
+            frame,
+            Line::new(Pixel((0, i * 10)), Pixel((799, 599 - i * 10))),
+            mode,
+        );
+    }
+}
+
+fn bench(name: &str, mode: Mode) -> f64 {
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+
+    // warm up
+    draw_frame(&mut frame, mode);
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        draw_frame(&mut frame, mode);
+    }
+    let per_frame = start.elapsed().as_secs_f64() * 1e6 / FRAMES as f64;
+
+    println!("{:<10} {:>10.1} us/frame", name, per_frame);
+    per_frame
+}
+
+fn main() {
+    let collected = bench("collected", Mode::Collected);
+    let lazy = bench("lazy", Mode::Lazy);
+    let spans = bench("spans", Mode::Spans);
+
+    println!("lazy speedup  {:>7.2}x", collected / lazy);
+    println!("spans speedup {:>7.2}x", collected / spans);
+}
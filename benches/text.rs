@@ -0,0 +1,67 @@
+//! Compares drawing a frame of HUD text with every glyph rasterized again
+//! with taking the glyphs from a `GlyphCache`.
+//!
+//! Run with `cargo bench --bench text`.
+
+use std::time::Instant;
+
+use pixel_game_engine::{
+    draw::Drawable,
+    pixel::Pixel,
+    text::{GlyphCache, Text},
+};
+
+const FRAMES: u32 = 200;
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// Writes the pixels to a frame buffer, the same way `Buffer::draw` does
+fn draw(frame: &mut [u8], pixels: impl IntoIterator<Item = Pixel>) {
+    for pixel in pixels {
+        let index = ((WIDTH * pixel.y() + pixel.x()) * 4) as usize;
+        if let Some(raw_pixel) = frame.get_mut(index..index + 4) {
+            raw_pixel.copy_from_slice(&WHITE);
+        }
+    }
+}
+
+/// Draws one frame of text lines, like a HUD with a log
+fn draw_frame(frame: &mut [u8], cache: Option<&GlyphCache>) {
+    for i in 0..30u32 {
+        let line = format!("Score: {:>6}  Lives: 3  Level {}", i * 1250, i % 7);
+        let text = Text::new(Pixel((10, i * 20)), &line, 16);
+        let text = match cache {
+            Some(cache) => text.with_cache(cache.clone()),
+            None => text,
+        };
+
+        draw(frame, text.pixels());
+    }
+}
+
+fn bench(name: &str, cache: Option<&GlyphCache>) -> f64 {
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+
+    // warm up
+    draw_frame(&mut frame, cache);
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        draw_frame(&mut frame, cache);
+    }
+    let per_frame = start.elapsed().as_secs_f64() * 1e6 / FRAMES as f64;
+
+    println!("{:<10} {:>10.1} us/frame", name, per_frame);
+    per_frame
+}
+
+fn main() {
+    let cache = GlyphCache::default();
+
+    let uncached = bench("uncached", None);
+    let cached = bench("cached", Some(&cache));
+
+    println!("cache speedup {:>7.2}x", uncached / cached);
+    println!("{:?}", cache.stats());
+}
@@ -5,6 +5,7 @@ use pixel_game_engine::{
     engine,
     events::{EventLoop, KeyEvent},
     pixel::Pixel,
+    text::Text,
 };
 use winit::event::VirtualKeyCode;
 
@@ -219,9 +220,7 @@ fn main() -> ! {
         let score = format!("{} : {}", s.player_left.score, s.player_right.score);
         b.draw_text(
             DefaultColors::White.as_color(),
-            (WIDTH / 2 - 50, 2).into(),
-            50,
-            &score,
+            Text::new((WIDTH / 2 - 50, 2).into(), &score, 50),
         );
     };
 
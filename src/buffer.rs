@@ -3,32 +3,76 @@ use winit::window::Window;
 
 use crate::{
     color::Color,
-    draw::Drawable,
+    draw::{ColoredDrawable, CoverageDrawable, Drawable, SpanDrawable},
+    image::Image,
+    mask::{Connectivity, Mask},
+    paint::Paint,
     pixel::Pixel,
     primitives::{
-        Circle, FilledCircle, FilledRectangle, HollowCircle, HollowRectangle, Line, Rectangle,
+        Circle, Ellipse, FillRule, Line, Path, Polygon, Polyline, Rectangle, RoundedRectangle,
+        Span, Stroke, Triangle,
     },
-    text::Text,
+    text::{GlyphCache, RichText, Text, TextBox, TextEffects},
 };
 
 pub struct Buffer {
     window: Window,
     pixels: Pixels<Window>,
+    width: u32,
+    height: u32,
+    glyph_cache: GlyphCache,
 }
 
 /// initialization and field access
 impl Buffer {
-    pub fn new(window: Window, pixels: Pixels<Window>) -> Self {
-        Self { window, pixels }
+    /// `width` and `height` are the size of the pixel buffer, not of the window
+    pub fn new(window: Window, pixels: Pixels<Window>, width: u32, height: u32) -> Self {
+        Self {
+            window,
+            pixels,
+            width,
+            height,
+            glyph_cache: GlyphCache::default(),
+        }
     }
 
     pub fn window(&self) -> &Window {
         &self.window
     }
 
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Keeps the glyphs of all text drawn by the buffer across frames
+    pub fn glyph_cache(&self) -> &GlyphCache {
+        &self.glyph_cache
+    }
+
     pub fn render(&mut self) -> Result<(), Error> {
         self.pixels.render()
     }
+
+    /// Converts a physical position in the window to a pixel in the buffer.
+    /// Positions outside of the buffer are clamped to its bounds.
+    pub(crate) fn window_to_pixel(&self, position: (f32, f32)) -> Pixel {
+        let (x, y) = self
+            .pixels
+            .window_pos_to_pixel(position)
+            .unwrap_or_else(|pos| self.pixels.clamp_pixel_pos(pos));
+
+        Pixel((x as u32, y as u32))
+    }
+
+    /// Resizes the surface the pixel buffer is rendered to.
+    /// The pixel buffer itself keeps its size and is scaled to fit.
+    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+        self.pixels.resize(width, height)
+    }
 }
 
 /// Drawing routines
@@ -36,95 +80,439 @@ impl Buffer {
     /// Converts the given pixel coordinates the the corresponding index
     /// into the raw pixel buffer
     fn pixel_to_index(&self, pixel: Pixel) -> usize {
-        ((self.window.inner_size().width * pixel.y() + pixel.x()) * 4) as usize
+        ((self.width as usize * pixel.y() as usize) + pixel.x() as usize) * 4
     }
 
     /// Tries to get the raw pixel buffer at the given pixel position.
-    /// If the position is out of the window bounds, will return None.
+    /// If the position is out of the buffer bounds, will return None.
     fn get_pixel(&mut self, pixel: Pixel) -> Option<&mut [u8]> {
+        if pixel.x() >= self.width {
+            return None;
+        }
+
         let index = self.pixel_to_index(pixel);
 
         self.pixels.get_frame().get_mut(index..index + 4)
     }
 
+    /// Gets the raw pixels covered by the span, clipped to the buffer bounds
+    fn get_span(&mut self, span: Span) -> Option<&mut [u8]> {
+        let end = span.end.min(self.width);
+        if span.y >= self.height || span.start >= end {
+            return None;
+        }
+
+        let start_index = self.pixel_to_index(Pixel((span.start, span.y)));
+        let end_index = self.pixel_to_index(Pixel((end, span.y)));
+
+        self.pixels.get_frame().get_mut(start_index..end_index)
+    }
+
+    /// The color currently in the buffer at the given pixel
+    pub fn color_at(&mut self, pixel: Pixel) -> Option<Color> {
+        self.get_pixel(pixel)
+            .map(|raw| Color([raw[0], raw[1], raw[2], raw[3]]))
+    }
+
+    /// Selects the area connected to the seed, in which the predicate holds for every pixel.
+    /// The mask can be used for selection tools or drawn with [`fill`](Buffer::fill).
+    pub fn select_region(
+        &mut self,
+        seed: Pixel,
+        connectivity: Connectivity,
+        mut predicate: impl FnMut(Pixel, Color) -> bool,
+    ) -> Mask {
+        let (width, height) = (self.width, self.height);
+        let frame = self.pixels.get_frame();
+
+        Mask::flood(width, height, seed, connectivity, |pixel| {
+            let index = (pixel.y() as usize * width as usize + pixel.x() as usize) * 4;
+            match frame.get(index..index + 4) {
+                Some(raw) => predicate(pixel, Color([raw[0], raw[1], raw[2], raw[3]])),
+                None => false,
+            }
+        })
+    }
+
+    /// Bucket fill, replacing the area around the seed, whose colors differ from the seed color
+    /// by at most `tolerance` in each channel
+    pub fn flood_fill(
+        &mut self,
+        seed: Pixel,
+        color: impl Into<Color>,
+        tolerance: u8,
+        connectivity: Connectivity,
+    ) {
+        let target = match self.color_at(seed) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let region = self.select_region(seed, connectivity, |_, color| {
+            target.is_close_to(color, tolerance)
+        });
+        self.fill(color, region)
+    }
+
     /// Draw the given color for the given pixel
-    pub fn draw_pixel(&mut self, pixel: Pixel, color: Color) {
+    pub fn draw_pixel(&mut self, pixel: Pixel, color: impl Into<Color>) {
+        let color = color.into();
         if let Some(raw_pixel) = self.get_pixel(pixel) {
-            raw_pixel.copy_from_slice(&color)
+            raw_pixel.copy_from_slice(&color.0)
+        }
+    }
+
+    /// Composite the given color over the pixel already in the buffer
+    pub fn blend_pixel(&mut self, pixel: Pixel, color: impl Into<Color>) {
+        let color = color.into();
+        if let Some(raw_pixel) = self.get_pixel(pixel) {
+            let background = Color([raw_pixel[0], raw_pixel[1], raw_pixel[2], raw_pixel[3]]);
+            raw_pixel.copy_from_slice(&color.blend_over(background).0)
         }
     }
 
     /// Clear the whole window to the given color
-    pub fn clear(&mut self, color: Color) {
+    pub fn clear(&mut self, color: impl Into<Color>) {
+        let color = color.into();
         for raw_pixel in self.pixels.get_frame().chunks_exact_mut(4) {
-            raw_pixel.copy_from_slice(&color)
+            raw_pixel.copy_from_slice(&color.0)
         }
     }
 
-    // TODO: Create a drawable trait and have a generic draw method for it.
-    // Can be used to draw arbitrary user structs
-    pub fn draw(&mut self, color: Color, drawable: impl Drawable) {
+    /// Fill a horizontal run of pixels with the given color
+    pub fn fill_span(&mut self, color: impl Into<Color>, span: Span) {
+        let color = color.into();
+        if let Some(raw_pixels) = self.get_span(span) {
+            for raw_pixel in raw_pixels.chunks_exact_mut(4) {
+                raw_pixel.copy_from_slice(&color.0)
+            }
+        }
+    }
+
+    /// Fill a shape made of spans, writing a row slice per span
+    /// instead of going through every pixel on its own
+    pub fn fill(&mut self, color: impl Into<Color>, shape: impl SpanDrawable) {
+        let color = color.into();
+        for span in shape.spans() {
+            self.fill_span(color, span)
+        }
+    }
+
+    /// Fill a shape made of spans, taking the color of each pixel from the paint,
+    /// e.g. a gradient or pattern
+    pub fn fill_paint(&mut self, paint: impl Paint, shape: impl SpanDrawable) {
+        for span in shape.spans() {
+            let (start, y) = (span.start, span.y);
+            if let Some(raw_pixels) = self.get_span(span) {
+                for (x, raw_pixel) in (start..).zip(raw_pixels.chunks_exact_mut(4)) {
+                    raw_pixel.copy_from_slice(&paint.color_at(Pixel((x, y))).0)
+                }
+            }
+        }
+    }
+
+    /// Calls the shader for every pixel of the region, clipped to the buffer,
+    /// and draws the color it returns
+    pub fn fill_with(&mut self, region: Rectangle, mut shader: impl FnMut(u32, u32) -> Color) {
+        self.map_pixels(region, |x, y, _| shader(x, y))
+    }
+
+    /// Replaces every pixel of the region, clipped to the buffer,
+    /// with the color returned for its position and current color
+    pub fn map_pixels(&mut self, region: Rectangle, mut f: impl FnMut(u32, u32, Color) -> Color) {
+        for span in region.filled().spans() {
+            let (start, y) = (span.start, span.y);
+            if let Some(raw_pixels) = self.get_span(span) {
+                for (x, raw_pixel) in (start..).zip(raw_pixels.chunks_exact_mut(4)) {
+                    let current = Color([raw_pixel[0], raw_pixel[1], raw_pixel[2], raw_pixel[3]]);
+                    raw_pixel.copy_from_slice(&f(x, y, current).0)
+                }
+            }
+        }
+    }
+
+    /// Clear the given region of the window to the given color
+    pub fn clear_region(&mut self, color: impl Into<Color>, region: Rectangle) {
+        self.fill(color, region.filled())
+    }
+
+    /// Draw any drawable in the given color,
+    /// e.g. a shape from primitives or a user struct implementing Drawable
+    pub fn draw(&mut self, color: impl Into<Color>, drawable: impl Drawable) {
+        let color = color.into();
         drawable
             .pixels()
             .into_iter()
             .for_each(|p| self.draw_pixel(p, color))
     }
 
-    pub fn draw_circle(&mut self, color: Color, center: Pixel, radius: u32, filled: bool) {
+    /// Blend any soft edged shape in the given color, scaling its alpha by the coverage
+    pub fn blend(&mut self, color: impl Into<Color>, shape: impl CoverageDrawable) {
+        let color = color.into();
+        for (pixel, coverage) in shape.coverage() {
+            let alpha = (color.a() as f32 * coverage.clamp(0.0, 1.0)).round() as u8;
+            self.blend_pixel(pixel, color.with_alpha(alpha))
+        }
+    }
+
+    /// Draw an image, e.g. a sprite, with its top left corner at the pixel.
+    /// Runs of opaque pixels are copied into the row, the other pixels are blended over it.
+    pub fn draw_image(&mut self, image: &Image, top_left: Pixel) {
+        let width = image.width() as usize;
+        for (row, colors) in (0..).zip(image.colors().chunks_exact(width)) {
+            let y = match top_left.y().checked_add(row) {
+                Some(y) if y < self.height => y,
+                _ => return,
+            };
+
+            let mut start = 0;
+            while start < width {
+                let opaque = colors[start].a() == 255;
+                let end = colors[start..]
+                    .iter()
+                    .position(|color| (color.a() == 255) != opaque)
+                    .map_or(width, |len| start + len);
+                let x = top_left.x().saturating_add(start as u32);
+                if x >= self.width {
+                    break;
+                }
+
+                if opaque {
+                    let span = Span::new(y, x, top_left.x().saturating_add(end as u32));
+                    if let Some(raw_pixels) = self.get_span(span) {
+                        // the span is cut off at the right edge of the buffer
+                        for (raw_pixel, color) in
+                            raw_pixels.chunks_exact_mut(4).zip(&colors[start..end])
+                        {
+                            raw_pixel.copy_from_slice(&color.0)
+                        }
+                    }
+                } else {
+                    for (x, color) in (x..).zip(&colors[start..end]) {
+                        if color.a() > 0 {
+                            self.blend_pixel(Pixel((x, y)), *color)
+                        }
+                    }
+                }
+
+                start = end;
+            }
+        }
+    }
+
+    /// Draw a drawable, which brings its own colors
+    pub fn draw_colored(&mut self, drawable: impl ColoredDrawable) {
+        drawable
+            .colored_pixels()
+            .into_iter()
+            .for_each(|(p, color)| self.draw_pixel(p, color))
+    }
+
+    pub fn draw_circle(
+        &mut self,
+        color: impl Into<Color>,
+        center: Pixel,
+        radius: u32,
+        filled: bool,
+    ) {
         if !filled {
-            self.draw(color, HollowCircle(Circle { center, radius }))
+            self.draw(color, Circle::new(center, radius).hollow())
         } else {
-            self.draw(color, FilledCircle(Circle { center, radius }))
+            self.fill(color, Circle::new(center, radius).filled())
         }
     }
 
-    pub fn draw_line(&mut self, color: Color, start: Pixel, end: Pixel) {
-        self.draw(
-            color,
-            Line {
-                from: start,
-                to: end,
-            },
-        )
+    pub fn draw_ellipse(
+        &mut self,
+        color: impl Into<Color>,
+        center: Pixel,
+        radius_x: u32,
+        radius_y: u32,
+        filled: bool,
+    ) {
+        let ellipse = Ellipse::new(center, radius_x, radius_y);
+        if !filled {
+            self.draw(color, ellipse.hollow())
+        } else {
+            self.fill(color, ellipse.filled())
+        }
+    }
+
+    /// Angles are in radians, see [`Arc`](crate::primitives::Arc)
+    pub fn draw_arc(
+        &mut self,
+        color: impl Into<Color>,
+        center: Pixel,
+        radius: u32,
+        start: f32,
+        end: f32,
+    ) {
+        self.draw(color, Circle::new(center, radius).arc(start, end))
+    }
+
+    /// Angles are in radians, see [`Arc`](crate::primitives::Arc)
+    pub fn draw_pie(
+        &mut self,
+        color: impl Into<Color>,
+        center: Pixel,
+        radius: u32,
+        start: f32,
+        end: f32,
+        filled: bool,
+    ) {
+        let pie = Circle::new(center, radius).pie(start, end);
+        if !filled {
+            self.draw(color, pie.hollow())
+        } else {
+            self.fill(color, pie.filled())
+        }
+    }
+
+    pub fn draw_line(&mut self, color: impl Into<Color>, start: Pixel, end: Pixel) {
+        self.draw(color, Line::new(start, end))
+    }
+
+    /// The pattern decides which pixels are drawn, see [`Line::dashed`]
+    pub fn draw_dashed_line(
+        &mut self,
+        color: impl Into<Color>,
+        start: Pixel,
+        end: Pixel,
+        pattern: u32,
+    ) {
+        self.draw(color, Line::new(start, end).dashed(pattern))
+    }
+
+    pub fn draw_thick_line(
+        &mut self,
+        color: impl Into<Color>,
+        start: Pixel,
+        end: Pixel,
+        stroke: Stroke,
+    ) {
+        self.fill(color, Line::new(start, end).stroked(stroke))
+    }
+
+    pub fn draw_line_anti_aliased(&mut self, color: impl Into<Color>, start: Pixel, end: Pixel) {
+        self.blend(color, Line::new(start, end).anti_aliased())
     }
 
     pub fn draw_rectangle(
         &mut self,
-        color: Color,
+        color: impl Into<Color>,
         top_left: Pixel,
         width: u32,
         height: u32,
         filled: bool,
     ) {
         if !filled {
-            self.draw(
-                color,
-                HollowRectangle(Rectangle {
-                    top_left,
-                    width,
-                    height,
-                }),
-            )
+            self.draw(color, Rectangle::new(top_left, width, height).hollow())
         } else {
-            self.draw(
-                color,
-                FilledRectangle(Rectangle {
-                    top_left,
-                    width,
-                    height,
-                }),
-            )
+            self.fill(color, Rectangle::new(top_left, width, height).filled())
         }
     }
 
-    pub fn draw_text(&mut self, color: Color, top_left: Pixel, height: u32, text: &str) {
+    pub fn draw_rounded_rectangle(
+        &mut self,
+        color: impl Into<Color>,
+        top_left: Pixel,
+        width: u32,
+        height: u32,
+        radius: u32,
+        filled: bool,
+    ) {
+        let rect = RoundedRectangle::new(top_left, width, height, radius);
+        if !filled {
+            self.draw(color, rect.hollow())
+        } else {
+            self.fill(color, rect.filled())
+        }
+    }
+
+    pub fn draw_triangle(
+        &mut self,
+        color: impl Into<Color>,
+        a: Pixel,
+        b: Pixel,
+        c: Pixel,
+        filled: bool,
+    ) {
+        if !filled {
+            self.draw(color, Triangle::new(a, b, c).hollow())
+        } else {
+            self.fill(color, Triangle::new(a, b, c).filled())
+        }
+    }
+
+    /// Filled polygons use the non-zero rule, fill a [`Polygon`] for even-odd
+    pub fn draw_polygon(&mut self, color: impl Into<Color>, vertices: &[Pixel], filled: bool) {
+        if !filled {
+            self.draw(color, Polygon::new(vertices.to_vec()).hollow())
+        } else {
+            self.fill(color, Polygon::new(vertices.to_vec()).filled())
+        }
+    }
+
+    pub fn draw_polyline(&mut self, color: impl Into<Color>, vertices: &[Pixel]) {
+        self.draw(color, Polyline::new(vertices.to_vec()))
+    }
+
+    pub fn fill_path(&mut self, color: impl Into<Color>, path: &Path, fill_rule: FillRule) {
+        self.fill(color, path.clone().filled(fill_rule))
+    }
+
+    pub fn stroke_path(&mut self, color: impl Into<Color>, path: &Path, stroke: Stroke) {
+        self.fill(color, path.clone().stroked(stroke))
+    }
+
+    /// Draw a line of text, e.g. `Text::new(top_left, "Score", 16)`,
+    /// with its font, alignment, anti-aliasing and effects.
+    /// Glyphs are taken from the glyph cache.
+    pub fn draw_text(&mut self, color: impl Into<Color>, text: Text) {
+        let color = color.into();
+        let text = text.with_cache(self.glyph_cache.clone());
+
+        if text.effects != TextEffects::new() {
+            let mut effects = text
+                .clone()
+                .decorated(color)
+                .colored_pixels()
+                .without_glyphs();
+            if let Some((color, background)) = effects.take_background() {
+                self.fill(color, background)
+            }
+            effects.for_each(|(p, color)| self.draw_pixel(p, color));
+        }
+
+        if text.anti_aliased {
+            self.blend(color, text)
+        } else {
+            self.draw(color, text)
+        }
+    }
+
+    /// Draw text wrapped into the area, the parts not fitting into it are clipped.
+    /// For other options draw a [`TextBox`](crate::text::TextBox).
+    pub fn draw_text_box(
+        &mut self,
+        color: impl Into<Color>,
+        area: Rectangle,
+        height: u32,
+        text: &str,
+    ) {
         self.draw(
             color,
-            Text {
-                position: top_left,
-                content: text,
-                height,
-            },
+            TextBox::new(area, text, height).with_cache(self.glyph_cache.clone()),
         )
     }
+
+    /// Draw text with inline markup, e.g. `"Press [color=yellow]Space[/color] to jump"`.
+    /// Glyphs are taken from the glyph cache, icons are drawn as images.
+    pub fn draw_rich_text(&mut self, text: RichText) {
+        let (glyphs, icons) = text.with_cache(self.glyph_cache.clone()).layout();
+        glyphs.for_each(|(pixel, color)| self.draw_pixel(pixel, color));
+        for (top_left, icon) in icons {
+            self.draw_image(&icon, top_left)
+        }
+    }
 }
@@ -0,0 +1,363 @@
+mod named;
+
+/// An RGBA color with straight (not premultiplied) alpha.
+/// Assumes the default texture format: `wgpu::TextureFormat::Rgba8UnormSrgb`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color(pub [u8; 4]);
+
+/// Construction and field access
+impl Color {
+    pub const TRANSPARENT: Color = Color([0, 0, 0, 0]);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color([r, g, b, 255])
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color([r, g, b, a])
+    }
+
+    pub fn r(&self) -> u8 {
+        self.0[0]
+    }
+    pub fn g(&self) -> u8 {
+        self.0[1]
+    }
+    pub fn b(&self) -> u8 {
+        self.0[2]
+    }
+    pub fn a(&self) -> u8 {
+        self.0[3]
+    }
+
+    pub fn with_alpha(self, a: u8) -> Self {
+        Color([self.r(), self.g(), self.b(), a])
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa`, the leading `#` is optional
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        // from_str_radix alone would accept signs, e.g. in `+f+f+f`
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok();
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        match hex.len() {
+            3 | 4 => {
+                let mut channels = [255u8; 4];
+                for (i, channel) in channels.iter_mut().enumerate().take(hex.len()) {
+                    // expand a single digit, e.g. f to ff
+                    *channel = digit(i)? * 17;
+                }
+                Some(Color(channels))
+            }
+            6 | 8 => {
+                let mut channels = [255u8; 4];
+                for (i, channel) in channels.iter_mut().enumerate().take(hex.len() / 2) {
+                    *channel = byte(i * 2)?;
+                }
+                Some(Color(channels))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether each channel differs from the other color by at most `tolerance`
+    pub(crate) fn is_close_to(&self, other: Color, tolerance: u8) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(a, b)| (*a as i16 - *b as i16).abs() <= tolerance as i16)
+    }
+
+    /// Formats as `#rrggbb`, or `#rrggbbaa` if the color is not opaque
+    pub fn to_hex(&self) -> String {
+        if self.a() == 255 {
+            format!("#{:02x}{:02x}{:02x}", self.r(), self.g(), self.b())
+        } else {
+            format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                self.r(),
+                self.g(),
+                self.b(),
+                self.a()
+            )
+        }
+    }
+}
+
+fn to_unit(channel: u8) -> f32 {
+    channel as f32 / 255.0
+}
+
+fn from_unit(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Hue in degrees, chroma and value minus chroma to rgb
+fn from_hue_chroma(hue: f32, chroma: f32, m: f32) -> (f32, f32, f32) {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    (r + m, g + m, b + m)
+}
+
+/// Color space conversions
+impl Color {
+    /// Hue in degrees, saturation and value from 0.0 to 1.0
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let chroma = value * saturation;
+        let (r, g, b) = from_hue_chroma(hue, chroma, value - chroma);
+
+        Color([from_unit(r), from_unit(g), from_unit(b), 255])
+    }
+
+    /// Hue in degrees, saturation and lightness from 0.0 to 1.0
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let (r, g, b) = from_hue_chroma(hue, chroma, lightness - chroma / 2.0);
+
+        Color([from_unit(r), from_unit(g), from_unit(b), 255])
+    }
+
+    /// Returns hue, max and min of the normalized rgb channels
+    fn hue_max_min(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (to_unit(self.r()), to_unit(self.g()), to_unit(self.b()));
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, max, min)
+    }
+
+    /// Returns hue in degrees, saturation and value, ignoring alpha
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        (hue, saturation, max)
+    }
+
+    /// Returns hue in degrees, saturation and lightness, ignoring alpha
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        (hue, saturation, lightness)
+    }
+}
+
+/// Color manipulation
+impl Color {
+    /// Linear interpolation of all channels, `t` is clamped to 0.0..=1.0
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mut channels = [0u8; 4];
+        for (i, channel) in channels.iter_mut().enumerate() {
+            let from = self.0[i] as f32;
+            let to = other.0[i] as f32;
+            *channel = (from + (to - from) * t).round() as u8;
+        }
+
+        Color(channels)
+    }
+
+    /// Scales the rgb channels by the given factor, keeping alpha
+    pub fn with_brightness(self, factor: f32) -> Self {
+        let scale = |channel: u8| (channel as f32 * factor).round().clamp(0.0, 255.0) as u8;
+
+        Color([scale(self.r()), scale(self.g()), scale(self.b()), self.a()])
+    }
+
+    /// Moves the color towards white by `amount`, keeping alpha
+    pub fn lighten(self, amount: f32) -> Self {
+        self.lerp(Color::WHITE.with_alpha(self.a()), amount)
+    }
+
+    /// Moves the color towards black by `amount`, keeping alpha
+    pub fn darken(self, amount: f32) -> Self {
+        self.lerp(Color::BLACK.with_alpha(self.a()), amount)
+    }
+
+    /// Multiplies the rgb channels with alpha
+    pub fn premultiplied(self) -> Self {
+        let multiply = |channel: u8| ((channel as u16 * self.a() as u16 + 127) / 255) as u8;
+
+        Color([
+            multiply(self.r()),
+            multiply(self.g()),
+            multiply(self.b()),
+            self.a(),
+        ])
+    }
+
+    /// Reverts [`premultiplied`](Color::premultiplied), a fully transparent color stays black
+    pub fn unpremultiplied(self) -> Self {
+        if self.a() == 0 {
+            return Color::TRANSPARENT;
+        }
+
+        let divide = |channel: u8| {
+            ((channel as u16 * 255 + self.a() as u16 / 2) / self.a() as u16).min(255) as u8
+        };
+
+        Color([
+            divide(self.r()),
+            divide(self.g()),
+            divide(self.b()),
+            self.a(),
+        ])
+    }
+
+    /// Composites this color over the given background (source-over)
+    pub fn blend_over(self, background: Color) -> Self {
+        match self.a() {
+            255 => return self,
+            0 => return background,
+            _ => (),
+        }
+
+        let src_a = to_unit(self.a());
+        let dst_a = to_unit(background.a()) * (1.0 - src_a);
+        let out_a = src_a + dst_a;
+
+        let blend =
+            |src: u8, dst: u8| from_unit((to_unit(src) * src_a + to_unit(dst) * dst_a) / out_a);
+
+        Color([
+            blend(self.r(), background.r()),
+            blend(self.g(), background.g()),
+            blend(self.b(), background.b()),
+            from_unit(out_a),
+        ])
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from(channels: [u8; 4]) -> Self {
+        Color(channels)
+    }
+}
+
+impl From<Color> for [u8; 4] {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color::rgb(r, g, b)
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Color {
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Self {
+        Color::rgba(r, g, b, a)
+    }
+}
+
+#[non_exhaustive]
+pub enum DefaultColors {
+    Black,
+    White,
+}
+
+impl DefaultColors {
+    pub fn as_color(&self) -> Color {
+        match *self {
+            DefaultColors::Black => Color::BLACK,
+            DefaultColors::White => Color::WHITE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Color::from_hex("#ff8000"), Some(Color::rgb(255, 128, 0)));
+        assert_eq!(Color::from_hex("f80"), Some(Color::rgb(255, 136, 0)));
+        assert_eq!(
+            Color::from_hex("#ff800080"),
+            Some(Color::rgba(255, 128, 0, 128))
+        );
+        assert_eq!(
+            Color::from_hex("#ff80"),
+            Some(Color::rgba(255, 255, 136, 0))
+        );
+        assert_eq!(Color::from_hex("#ff80zz"), None);
+        assert_eq!(Color::from_hex("#ff800"), None);
+        assert_eq!(Color::from_hex("#+f+f+f"), None);
+        assert_eq!(Color::from_hex("##fff"), None);
+        assert_eq!(Color::from_hex("###ffffff"), None);
+        assert_eq!(Color::rgba(255, 128, 0, 128).to_hex(), "#ff800080");
+    }
+
+    #[test]
+    fn named_colors() {
+        assert_eq!(
+            Color::from_name("Cornflower Blue"),
+            Some(Color::CORNFLOWER_BLUE)
+        );
+        assert_eq!(
+            Color::from_name("rebeccapurple"),
+            Some(Color::rgb(102, 51, 153))
+        );
+        assert_eq!(Color::from_name("not a color"), None);
+    }
+
+    #[test]
+    fn hsv_and_hsl_round_trip() {
+        for color in &[Color::ORANGE, Color::TEAL, Color::CRIMSON, Color::GRAY] {
+            let (h, s, v) = color.to_hsv();
+            assert_eq!(Color::from_hsv(h, s, v), *color);
+
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l), *color);
+        }
+    }
+
+    #[test]
+    fn alpha_blending() {
+        let half_red = Color::RED.with_alpha(128);
+
+        assert_eq!(half_red.premultiplied(), Color::rgba(128, 0, 0, 128));
+        assert_eq!(half_red.premultiplied().unpremultiplied(), half_red);
+        assert_eq!(half_red.blend_over(Color::BLUE), Color::rgb(128, 0, 127));
+        assert_eq!(
+            Color::BLACK.lerp(Color::WHITE, 0.5),
+            Color::rgb(128, 128, 128)
+        );
+    }
+}
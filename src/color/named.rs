@@ -0,0 +1,176 @@
+use super::Color;
+
+macro_rules! named_colors {
+    ($($constant:ident = $name:ident: $value:expr,)*) => {
+        /// The named colors of the CSS color module
+        impl Color {
+            $(pub const $constant: Color = Color($value);)*
+
+            /// Looks up a named color, ignoring case, spaces, dashes and underscores
+            pub fn from_name(name: &str) -> Option<Color> {
+                let name: String = name
+                    .chars()
+                    .filter(|c| !matches!(c, ' ' | '-' | '_'))
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect();
+
+                match name.as_str() {
+                    "transparent" => Some(Color::TRANSPARENT),
+                    $(stringify!($name) => Some(Color::$constant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+named_colors! {
+    ALICE_BLUE = aliceblue: [240, 248, 255, 255],
+    ANTIQUE_WHITE = antiquewhite: [250, 235, 215, 255],
+    AQUA = aqua: [0, 255, 255, 255],
+    AQUAMARINE = aquamarine: [127, 255, 212, 255],
+    AZURE = azure: [240, 255, 255, 255],
+    BEIGE = beige: [245, 245, 220, 255],
+    BISQUE = bisque: [255, 228, 196, 255],
+    BLACK = black: [0, 0, 0, 255],
+    BLANCHED_ALMOND = blanchedalmond: [255, 235, 205, 255],
+    BLUE = blue: [0, 0, 255, 255],
+    BLUE_VIOLET = blueviolet: [138, 43, 226, 255],
+    BROWN = brown: [165, 42, 42, 255],
+    BURLY_WOOD = burlywood: [222, 184, 135, 255],
+    CADET_BLUE = cadetblue: [95, 158, 160, 255],
+    CHARTREUSE = chartreuse: [127, 255, 0, 255],
+    CHOCOLATE = chocolate: [210, 105, 30, 255],
+    CORAL = coral: [255, 127, 80, 255],
+    CORNFLOWER_BLUE = cornflowerblue: [100, 149, 237, 255],
+    CORNSILK = cornsilk: [255, 248, 220, 255],
+    CRIMSON = crimson: [220, 20, 60, 255],
+    CYAN = cyan: [0, 255, 255, 255],
+    DARK_BLUE = darkblue: [0, 0, 139, 255],
+    DARK_CYAN = darkcyan: [0, 139, 139, 255],
+    DARK_GOLDENROD = darkgoldenrod: [184, 134, 11, 255],
+    DARK_GRAY = darkgray: [169, 169, 169, 255],
+    DARK_GREEN = darkgreen: [0, 100, 0, 255],
+    DARK_GREY = darkgrey: [169, 169, 169, 255],
+    DARK_KHAKI = darkkhaki: [189, 183, 107, 255],
+    DARK_MAGENTA = darkmagenta: [139, 0, 139, 255],
+    DARK_OLIVE_GREEN = darkolivegreen: [85, 107, 47, 255],
+    DARK_ORANGE = darkorange: [255, 140, 0, 255],
+    DARK_ORCHID = darkorchid: [153, 50, 204, 255],
+    DARK_RED = darkred: [139, 0, 0, 255],
+    DARK_SALMON = darksalmon: [233, 150, 122, 255],
+    DARK_SEA_GREEN = darkseagreen: [143, 188, 143, 255],
+    DARK_SLATE_BLUE = darkslateblue: [72, 61, 139, 255],
+    DARK_SLATE_GRAY = darkslategray: [47, 79, 79, 255],
+    DARK_SLATE_GREY = darkslategrey: [47, 79, 79, 255],
+    DARK_TURQUOISE = darkturquoise: [0, 206, 209, 255],
+    DARK_VIOLET = darkviolet: [148, 0, 211, 255],
+    DEEP_PINK = deeppink: [255, 20, 147, 255],
+    DEEP_SKY_BLUE = deepskyblue: [0, 191, 255, 255],
+    DIM_GRAY = dimgray: [105, 105, 105, 255],
+    DIM_GREY = dimgrey: [105, 105, 105, 255],
+    DODGER_BLUE = dodgerblue: [30, 144, 255, 255],
+    FIREBRICK = firebrick: [178, 34, 34, 255],
+    FLORAL_WHITE = floralwhite: [255, 250, 240, 255],
+    FOREST_GREEN = forestgreen: [34, 139, 34, 255],
+    FUCHSIA = fuchsia: [255, 0, 255, 255],
+    GAINSBORO = gainsboro: [220, 220, 220, 255],
+    GHOST_WHITE = ghostwhite: [248, 248, 255, 255],
+    GOLD = gold: [255, 215, 0, 255],
+    GOLDENROD = goldenrod: [218, 165, 32, 255],
+    GRAY = gray: [128, 128, 128, 255],
+    GREEN = green: [0, 128, 0, 255],
+    GREEN_YELLOW = greenyellow: [173, 255, 47, 255],
+    GREY = grey: [128, 128, 128, 255],
+    HONEYDEW = honeydew: [240, 255, 240, 255],
+    HOT_PINK = hotpink: [255, 105, 180, 255],
+    INDIAN_RED = indianred: [205, 92, 92, 255],
+    INDIGO = indigo: [75, 0, 130, 255],
+    IVORY = ivory: [255, 255, 240, 255],
+    KHAKI = khaki: [240, 230, 140, 255],
+    LAVENDER = lavender: [230, 230, 250, 255],
+    LAVENDER_BLUSH = lavenderblush: [255, 240, 245, 255],
+    LAWN_GREEN = lawngreen: [124, 252, 0, 255],
+    LEMON_CHIFFON = lemonchiffon: [255, 250, 205, 255],
+    LIGHT_BLUE = lightblue: [173, 216, 230, 255],
+    LIGHT_CORAL = lightcoral: [240, 128, 128, 255],
+    LIGHT_CYAN = lightcyan: [224, 255, 255, 255],
+    LIGHT_GOLDENROD_YELLOW = lightgoldenrodyellow: [250, 250, 210, 255],
+    LIGHT_GRAY = lightgray: [211, 211, 211, 255],
+    LIGHT_GREEN = lightgreen: [144, 238, 144, 255],
+    LIGHT_GREY = lightgrey: [211, 211, 211, 255],
+    LIGHT_PINK = lightpink: [255, 182, 193, 255],
+    LIGHT_SALMON = lightsalmon: [255, 160, 122, 255],
+    LIGHT_SEA_GREEN = lightseagreen: [32, 178, 170, 255],
+    LIGHT_SKY_BLUE = lightskyblue: [135, 206, 250, 255],
+    LIGHT_SLATE_GRAY = lightslategray: [119, 136, 153, 255],
+    LIGHT_SLATE_GREY = lightslategrey: [119, 136, 153, 255],
+    LIGHT_STEEL_BLUE = lightsteelblue: [176, 196, 222, 255],
+    LIGHT_YELLOW = lightyellow: [255, 255, 224, 255],
+    LIME = lime: [0, 255, 0, 255],
+    LIME_GREEN = limegreen: [50, 205, 50, 255],
+    LINEN = linen: [250, 240, 230, 255],
+    MAGENTA = magenta: [255, 0, 255, 255],
+    MAROON = maroon: [128, 0, 0, 255],
+    MEDIUM_AQUAMARINE = mediumaquamarine: [102, 205, 170, 255],
+    MEDIUM_BLUE = mediumblue: [0, 0, 205, 255],
+    MEDIUM_ORCHID = mediumorchid: [186, 85, 211, 255],
+    MEDIUM_PURPLE = mediumpurple: [147, 112, 219, 255],
+    MEDIUM_SEA_GREEN = mediumseagreen: [60, 179, 113, 255],
+    MEDIUM_SLATE_BLUE = mediumslateblue: [123, 104, 238, 255],
+    MEDIUM_SPRING_GREEN = mediumspringgreen: [0, 250, 154, 255],
+    MEDIUM_TURQUOISE = mediumturquoise: [72, 209, 204, 255],
+    MEDIUM_VIOLET_RED = mediumvioletred: [199, 21, 133, 255],
+    MIDNIGHT_BLUE = midnightblue: [25, 25, 112, 255],
+    MINT_CREAM = mintcream: [245, 255, 250, 255],
+    MISTY_ROSE = mistyrose: [255, 228, 225, 255],
+    MOCCASIN = moccasin: [255, 228, 181, 255],
+    NAVAJO_WHITE = navajowhite: [255, 222, 173, 255],
+    NAVY = navy: [0, 0, 128, 255],
+    OLD_LACE = oldlace: [253, 245, 230, 255],
+    OLIVE = olive: [128, 128, 0, 255],
+    OLIVE_DRAB = olivedrab: [107, 142, 35, 255],
+    ORANGE = orange: [255, 165, 0, 255],
+    ORANGE_RED = orangered: [255, 69, 0, 255],
+    ORCHID = orchid: [218, 112, 214, 255],
+    PALE_GOLDENROD = palegoldenrod: [238, 232, 170, 255],
+    PALE_GREEN = palegreen: [152, 251, 152, 255],
+    PALE_TURQUOISE = paleturquoise: [175, 238, 238, 255],
+    PALE_VIOLET_RED = palevioletred: [219, 112, 147, 255],
+    PAPAYA_WHIP = papayawhip: [255, 239, 213, 255],
+    PEACH_PUFF = peachpuff: [255, 218, 185, 255],
+    PERU = peru: [205, 133, 63, 255],
+    PINK = pink: [255, 192, 203, 255],
+    PLUM = plum: [221, 160, 221, 255],
+    POWDER_BLUE = powderblue: [176, 224, 230, 255],
+    PURPLE = purple: [128, 0, 128, 255],
+    REBECCA_PURPLE = rebeccapurple: [102, 51, 153, 255],
+    RED = red: [255, 0, 0, 255],
+    ROSY_BROWN = rosybrown: [188, 143, 143, 255],
+    ROYAL_BLUE = royalblue: [65, 105, 225, 255],
+    SADDLE_BROWN = saddlebrown: [139, 69, 19, 255],
+    SALMON = salmon: [250, 128, 114, 255],
+    SANDY_BROWN = sandybrown: [244, 164, 96, 255],
+    SEA_GREEN = seagreen: [46, 139, 87, 255],
+    SEASHELL = seashell: [255, 245, 238, 255],
+    SIENNA = sienna: [160, 82, 45, 255],
+    SILVER = silver: [192, 192, 192, 255],
+    SKY_BLUE = skyblue: [135, 206, 235, 255],
+    SLATE_BLUE = slateblue: [106, 90, 205, 255],
+    SLATE_GRAY = slategray: [112, 128, 144, 255],
+    SLATE_GREY = slategrey: [112, 128, 144, 255],
+    SNOW = snow: [255, 250, 250, 255],
+    SPRING_GREEN = springgreen: [0, 255, 127, 255],
+    STEEL_BLUE = steelblue: [70, 130, 180, 255],
+    TAN = tan: [210, 180, 140, 255],
+    TEAL = teal: [0, 128, 128, 255],
+    THISTLE = thistle: [216, 191, 216, 255],
+    TOMATO = tomato: [255, 99, 71, 255],
+    TURQUOISE = turquoise: [64, 224, 208, 255],
+    VIOLET = violet: [238, 130, 238, 255],
+    WHEAT = wheat: [245, 222, 179, 255],
+    WHITE = white: [255, 255, 255, 255],
+    WHITE_SMOKE = whitesmoke: [245, 245, 245, 255],
+    YELLOW = yellow: [255, 255, 0, 255],
+    YELLOW_GREEN = yellowgreen: [154, 205, 50, 255],
+}
@@ -1,6 +1,367 @@
-use crate::pixel::Pixel;
+use std::collections::HashSet;
 
+use crate::{
+    color::Color,
+    paint::Paint,
+    pixel::Pixel,
+    primitives::{Rectangle, Span},
+};
+
+/// Something drawn pixel by pixel, the pixels are produced lazily while drawing.
+/// Shapes do not allocate for this. Text allocates one scratch bitmap per `pixels` call,
+/// which is reused for all of its glyphs, and `union` and `intersection` remember
+/// the pixels already seen.
 pub trait Drawable {
     type IntoIter: IntoIterator<Item = Pixel>;
     fn pixels(&self) -> Self::IntoIter;
+
+    /// Moves all pixels by the given offset.
+    /// Pixels moved to negative coordinates are dropped.
+    fn translate(self, offset: (i32, i32)) -> Translated<Self>
+    where
+        Self: Sized,
+    {
+        Translated {
+            drawable: self,
+            offset,
+        }
+    }
+
+    /// Draws the pixels of both drawables, overlapping pixels are drawn twice
+    fn chain<D: Drawable>(self, other: D) -> Chained<Self, D>
+    where
+        Self: Sized,
+    {
+        Chained {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Draws every pixel covered by either drawable exactly once
+    fn union<D: Drawable>(self, other: D) -> Union<Self, D>
+    where
+        Self: Sized,
+    {
+        Union {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Draws only the pixels covered by both drawables
+    fn intersection<D: Drawable>(self, other: D) -> Intersection<Self, D>
+    where
+        Self: Sized,
+    {
+        Intersection {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Drops all pixels outside of the given rectangle
+    fn clip_to(self, rect: Rectangle) -> Clipped<Self>
+    where
+        Self: Sized,
+    {
+        Clipped {
+            drawable: self,
+            rect,
+        }
+    }
+
+    /// Colors each pixel with the paint, e.g. a gradient
+    fn painted<P: Paint + Clone>(self, paint: P) -> Painted<Self, P>
+    where
+        Self: Sized,
+    {
+        Painted {
+            drawable: self,
+            paint,
+        }
+    }
+}
+
+/// Shapes can be drawn by reference, so they can be stored and reused
+impl<D: Drawable> Drawable for &D {
+    type IntoIter = D::IntoIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        (*self).pixels()
+    }
+}
+
+/// Shapes made of horizontal runs of pixels,
+/// which the buffer can fill a whole row slice at a time.
+/// Spans of a shape should not overlap.
+pub trait SpanDrawable {
+    type Spans: IntoIterator<Item = Span>;
+    fn spans(&self) -> Self::Spans;
+}
+
+impl<D: SpanDrawable> SpanDrawable for &D {
+    type Spans = D::Spans;
+
+    fn spans(&self) -> Self::Spans {
+        (*self).spans()
+    }
+}
+
+/// Shapes with soft edges, yielding how much of each pixel is covered, from 0.0 to 1.0.
+/// The buffer blends the color with the coverage as alpha.
+pub trait CoverageDrawable {
+    type Coverage: IntoIterator<Item = (Pixel, f32)>;
+    fn coverage(&self) -> Self::Coverage;
+}
+
+impl<D: CoverageDrawable> CoverageDrawable for &D {
+    type Coverage = D::Coverage;
+
+    fn coverage(&self) -> Self::Coverage {
+        (*self).coverage()
+    }
+}
+
+/// Shapes, which decide the color of each of their pixels
+pub trait ColoredDrawable {
+    type Colored: IntoIterator<Item = (Pixel, Color)>;
+    fn colored_pixels(&self) -> Self::Colored;
+}
+
+impl<D: ColoredDrawable> ColoredDrawable for &D {
+    type Colored = D::Colored;
+
+    fn colored_pixels(&self) -> Self::Colored {
+        (*self).colored_pixels()
+    }
+}
+
+/// The iterator over the pixels of a drawable
+type PixelIter<D> = <<D as Drawable>::IntoIter as IntoIterator>::IntoIter;
+
+pub struct Translated<D> {
+    pub drawable: D,
+    pub offset: (i32, i32),
+}
+
+pub struct TranslatedIter<I> {
+    inner: I,
+    offset: (i64, i64),
+}
+
+impl<I: Iterator<Item = Pixel>> Iterator for TranslatedIter<I> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+        self.inner.by_ref().find_map(|p| {
+            let x = p.x() as i64 + offset.0;
+            let y = p.y() as i64 + offset.1;
+
+            if x < 0 || y < 0 {
+                None
+            } else {
+                Some(Pixel((x as u32, y as u32)))
+            }
+        })
+    }
+}
+
+impl<D: Drawable> Drawable for Translated<D> {
+    type IntoIter = TranslatedIter<PixelIter<D>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        TranslatedIter {
+            inner: self.drawable.pixels().into_iter(),
+            offset: (self.offset.0 as i64, self.offset.1 as i64),
+        }
+    }
+}
+
+pub struct Chained<A, B> {
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Drawable, B: Drawable> Drawable for Chained<A, B> {
+    type IntoIter = std::iter::Chain<PixelIter<A>, PixelIter<B>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        self.first.pixels().into_iter().chain(self.second.pixels())
+    }
+}
+
+pub struct Union<A, B> {
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Drawable, B: Drawable> Drawable for Union<A, B> {
+    type IntoIter = std::vec::IntoIter<Pixel>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        let mut seen: HashSet<Pixel> = HashSet::new();
+
+        self.first
+            .pixels()
+            .into_iter()
+            .chain(self.second.pixels())
+            .filter(|p| seen.insert(*p))
+            .collect::<Vec<Pixel>>()
+            .into_iter()
+    }
+}
+
+pub struct Intersection<A, B> {
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Drawable, B: Drawable> Drawable for Intersection<A, B> {
+    type IntoIter = std::vec::IntoIter<Pixel>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        let covered: HashSet<Pixel> = self.second.pixels().into_iter().collect();
+        let mut seen: HashSet<Pixel> = HashSet::new();
+
+        self.first
+            .pixels()
+            .into_iter()
+            .filter(|p| covered.contains(p) && seen.insert(*p))
+            .collect::<Vec<Pixel>>()
+            .into_iter()
+    }
+}
+
+pub struct Clipped<D> {
+    pub drawable: D,
+    pub rect: Rectangle,
+}
+
+pub struct ClippedIter<I> {
+    inner: I,
+    rect: Rectangle,
+}
+
+impl<I: Iterator<Item = Pixel>> Iterator for ClippedIter<I> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rect = self.rect;
+        self.inner.by_ref().find(|p| rect.contains(*p))
+    }
+}
+
+impl<D: Drawable> Drawable for Clipped<D> {
+    type IntoIter = ClippedIter<PixelIter<D>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        ClippedIter {
+            inner: self.drawable.pixels().into_iter(),
+            rect: self.rect,
+        }
+    }
+}
+
+pub struct Painted<D, P> {
+    pub drawable: D,
+    pub paint: P,
+}
+
+pub struct PaintedIter<I, P> {
+    inner: I,
+    paint: P,
+}
+
+impl<I: Iterator<Item = Pixel>, P: Paint> Iterator for PaintedIter<I, P> {
+    type Item = (Pixel, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pixel = self.inner.next()?;
+        Some((pixel, self.paint.color_at(pixel)))
+    }
+}
+
+impl<D: Drawable, P: Paint + Clone> ColoredDrawable for Painted<D, P> {
+    type Colored = PaintedIter<PixelIter<D>, P>;
+
+    fn colored_pixels(&self) -> Self::Colored {
+        PaintedIter {
+            inner: self.drawable.pixels().into_iter(),
+            paint: self.paint.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        paint::LinearGradient,
+        primitives::{FilledRectangle, Line},
+    };
+
+    #[test]
+    fn translate_drops_negative_pixels() {
+        // Arrange
+        let line = Line::new((0, 5).into(), (3, 5).into());
+
+        // Act
+        let pixels: Vec<Pixel> = line.translate((-1, 2)).pixels().collect();
+
+        // Assert
+        assert_eq!(pixels, vec![(0, 7).into(), (1, 7).into()]);
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        // Arrange
+        let a = FilledRectangle(Rectangle::new((0, 0).into(), 2, 2));
+        let b = FilledRectangle(Rectangle::new((1, 1).into(), 2, 2));
+
+        // Act
+        let union: HashSet<Pixel> = (&a).union(&b).pixels().collect();
+        let intersection: Vec<Pixel> = a.intersection(b).pixels().collect();
+
+        // Assert
+        assert_eq!(union.len(), 7);
+        assert_eq!(intersection, vec![(1, 1).into()]);
+    }
+
+    #[test]
+    fn clip_to_rect() {
+        // Arrange
+        let line = Line::new((0, 0).into(), (10, 0).into());
+
+        // Act
+        let clipped: Vec<Pixel> = line
+            .clip_to(Rectangle::new((2, 0).into(), 2, 1))
+            .pixels()
+            .collect();
+
+        // Assert
+        assert_eq!(clipped, vec![(2, 0).into(), (3, 0).into()]);
+    }
+
+    #[test]
+    fn painted_pixels() {
+        // Arrange
+        let line = Line::new((0, 0).into(), (4, 0).into());
+        let gradient = LinearGradient::new((0.0, 0.0), (4.0, 0.0))
+            .with_stop(0.0, Color::BLACK)
+            .with_stop(1.0, Color::WHITE);
+
+        // Act
+        let colors: Vec<Color> = line
+            .painted(gradient)
+            .colored_pixels()
+            .map(|(_, color)| color)
+            .collect();
+
+        // Assert
+        assert_eq!(colors.len(), 4);
+        assert_eq!(colors[0], Color::rgb(32, 32, 32));
+        assert_eq!(colors[3], Color::rgb(223, 223, 223));
+    }
 }
@@ -2,23 +2,41 @@ use pixels::{Error, Pixels, SurfaceTexture};
 use winit::dpi::LogicalSize;
 use winit::event_loop::EventLoop;
 use winit::window::WindowBuilder;
+use winit_input_helper::WinitInputHelper;
 
 use crate::{
     buffer::Buffer,
-    events::{KeyEvent, MouseEvent},
+    events::{
+        ActionEvent, GestureConfig, GestureDetector, GestureEvent, InputMap, KeyEvent, MouseEvent,
+        WindowEvent,
+    },
 };
 
-pub struct PixelGameEngineBuilder<T>
+type UpdateFn<T> = Box<dyn FnMut(&mut T)>;
+type DrawFn<T> = Box<dyn FnMut(&mut Buffer, &T)>;
+
+/// A boxed listener, called with the state and each event of type `Ev`
+type Listener<T, Ev> = Box<dyn FnMut(&mut T, &Ev)>;
+
+pub struct PixelGameEngineBuilder<T, E = ()>
 where
     T: Sized,
+    E: 'static,
 {
     state: T,
     width: u32,
     height: u32,
-    update_fn: Box<dyn FnMut(&mut T) -> ()>,
-    draw_fn: Box<dyn FnMut(&mut Buffer, &T) -> ()>,
-    key_events: Vec<Box<dyn FnMut(&mut T, &KeyEvent)>>,
-    mouse_events: Vec<Box<dyn FnMut(&mut T, &MouseEvent)>>,
+    update_fn: UpdateFn<T>,
+    draw_fn: DrawFn<T>,
+    key_events: Vec<Listener<T, KeyEvent>>,
+    mouse_events: Vec<Listener<T, MouseEvent>>,
+    input_map: InputMap,
+    action_events: Vec<Listener<T, ActionEvent>>,
+    window_events: Vec<Listener<T, WindowEvent>>,
+    pause_on_focus_lost: bool,
+    gesture_config: GestureConfig,
+    gesture_events: Vec<Listener<T, GestureEvent>>,
+    user_events: Vec<Listener<T, E>>,
 }
 
 impl<T> PixelGameEngineBuilder<T>
@@ -26,6 +44,18 @@ where
     T: Sized,
 {
     pub fn new(state: T) -> Self {
+        Self::new_with_user_events(state)
+    }
+}
+
+impl<T, E> PixelGameEngineBuilder<T, E>
+where
+    T: Sized,
+    E: 'static,
+{
+    /// Creates a builder for an engine receiving user events of type `E`,
+    /// sent through a [`UserEventProxy`](crate::events::UserEventProxy)
+    pub fn new_with_user_events(state: T) -> Self {
         Self {
             state,
             width: 800,
@@ -34,6 +64,13 @@ where
             draw_fn: Box::new(|_, _| ()),
             key_events: Vec::new(),
             mouse_events: Vec::new(),
+            input_map: InputMap::new(),
+            action_events: Vec::new(),
+            window_events: Vec::new(),
+            pause_on_focus_lost: false,
+            gesture_config: GestureConfig::default(),
+            gesture_events: Vec::new(),
+            user_events: Vec::new(),
         }
     }
 
@@ -47,19 +84,19 @@ where
         self
     }
 
-    pub fn with_update(mut self, update_fn: impl FnMut(&mut T) -> () + 'static) -> Self {
+    pub fn with_update(mut self, update_fn: impl FnMut(&mut T) + 'static) -> Self {
         self.update_fn = Box::new(update_fn);
         self
     }
 
-    pub fn with_draw(mut self, draw_fn: impl FnMut(&mut Buffer, &T) -> () + 'static) -> Self {
+    pub fn with_draw(mut self, draw_fn: impl FnMut(&mut Buffer, &T) + 'static) -> Self {
         self.draw_fn = Box::new(draw_fn);
         self
     }
 
     pub fn add_key_listener(
         mut self,
-        key_listener: impl FnMut(&mut T, &KeyEvent) -> () + 'static,
+        key_listener: impl FnMut(&mut T, &KeyEvent) + 'static,
     ) -> Self {
         self.key_events.push(Box::new(key_listener));
         self
@@ -67,52 +104,114 @@ where
 
     pub fn add_mouse_listener(
         mut self,
-        mouse_listener: impl FnMut(&mut T, &MouseEvent) -> () + 'static,
+        mouse_listener: impl FnMut(&mut T, &MouseEvent) + 'static,
     ) -> Self {
         self.mouse_events.push(Box::new(mouse_listener));
         self
     }
 
-    pub(crate) fn build(self, event_loop: &EventLoop<()>) -> PixelGameEngine<T> {
-        PixelGameEngine::new(
-            self.state,
-            self.width,
-            self.height,
-            self.update_fn,
-            self.draw_fn,
-            self.key_events,
-            self.mouse_events,
-            event_loop,
-        )
+    /// Sets the input map used to emit action events.
+    /// Keep a clone of the map to query or rebind actions at runtime.
+    pub fn with_input_map(mut self, input_map: InputMap) -> Self {
+        self.input_map = input_map;
+        self
+    }
+
+    pub fn add_action_listener(
+        mut self,
+        action_listener: impl FnMut(&mut T, &ActionEvent) + 'static,
+    ) -> Self {
+        self.action_events.push(Box::new(action_listener));
+        self
+    }
+
+    pub fn add_window_listener(
+        mut self,
+        window_listener: impl FnMut(&mut T, &WindowEvent) + 'static,
+    ) -> Self {
+        self.window_events.push(Box::new(window_listener));
+        self
+    }
+
+    /// If set, the update_fn will not be called while the window is not focused.
+    /// Drawing and event listeners are not affected.
+    pub fn with_pause_on_focus_lost(mut self, pause: bool) -> Self {
+        self.pause_on_focus_lost = pause;
+        self
+    }
+
+    /// Sets the thresholds and timings used to detect gestures
+    pub fn with_gesture_config(mut self, gesture_config: GestureConfig) -> Self {
+        self.gesture_config = gesture_config;
+        self
+    }
+
+    pub fn add_gesture_listener(
+        mut self,
+        gesture_listener: impl FnMut(&mut T, &GestureEvent) + 'static,
+    ) -> Self {
+        self.gesture_events.push(Box::new(gesture_listener));
+        self
+    }
+
+    pub fn add_user_event_listener(
+        mut self,
+        user_event_listener: impl FnMut(&mut T, &E) + 'static,
+    ) -> Self {
+        self.user_events.push(Box::new(user_event_listener));
+        self
+    }
+
+    pub(crate) fn build(self, event_loop: &EventLoop<E>) -> PixelGameEngine<T, E> {
+        PixelGameEngine::new(self, event_loop)
     }
 }
 
-pub struct PixelGameEngine<T>
+pub struct PixelGameEngine<T, E = ()>
 where
     T: Sized,
+    E: 'static,
 {
     state: T,
     pub(crate) buffer: Buffer,
-    update_fn: Box<dyn FnMut(&mut T) -> ()>,
-    draw_fn: Box<dyn FnMut(&mut Buffer, &T) -> ()>,
-    key_events: Vec<Box<dyn FnMut(&mut T, &KeyEvent)>>,
-    mouse_events: Vec<Box<dyn FnMut(&mut T, &MouseEvent)>>,
+    update_fn: UpdateFn<T>,
+    draw_fn: DrawFn<T>,
+    key_events: Vec<Listener<T, KeyEvent>>,
+    mouse_events: Vec<Listener<T, MouseEvent>>,
+    input_map: InputMap,
+    action_events: Vec<Listener<T, ActionEvent>>,
+    window_events: Vec<Listener<T, WindowEvent>>,
+    pause_on_focus_lost: bool,
+    focused: bool,
+    minimized: bool,
+    pub(crate) gestures: GestureDetector,
+    gesture_events: Vec<Listener<T, GestureEvent>>,
+    user_events: Vec<Listener<T, E>>,
 }
 
-impl<T> PixelGameEngine<T>
+impl<T, E> PixelGameEngine<T, E>
 where
     T: Sized,
+    E: 'static,
 {
-    fn new(
-        state: T,
-        width: u32,
-        height: u32,
-        update_fn: Box<dyn FnMut(&mut T) -> ()>,
-        draw_fn: Box<dyn FnMut(&mut Buffer, &T) -> ()>,
-        key_events: Vec<Box<dyn FnMut(&mut T, &KeyEvent)>>,
-        mouse_events: Vec<Box<dyn FnMut(&mut T, &MouseEvent)>>,
-        event_loop: &EventLoop<()>,
-    ) -> Self {
+    fn new(builder: PixelGameEngineBuilder<T, E>, event_loop: &EventLoop<E>) -> Self {
+        let PixelGameEngineBuilder {
+            state,
+            width,
+            height,
+            update_fn,
+            draw_fn,
+            key_events,
+            mouse_events,
+            input_map,
+            action_events,
+            window_events,
+            pause_on_focus_lost,
+            gesture_config,
+            gesture_events,
+            user_events,
+        } = builder;
+
         // initialize logger
         env_logger::init();
 
@@ -138,11 +237,20 @@ where
         // return self
         Self {
             state,
-            buffer: Buffer::new(window, pixels),
+            buffer: Buffer::new(window, pixels, width, height),
             update_fn,
             draw_fn,
             key_events,
             mouse_events,
+            input_map,
+            action_events,
+            window_events,
+            pause_on_focus_lost,
+            focused: true,
+            minimized: false,
+            gestures: GestureDetector::new(gesture_config),
+            gesture_events,
+            user_events,
         }
     }
 
@@ -150,11 +258,19 @@ where
         &self.state
     }
 
+    /// The engine is paused while the window is minimized,
+    /// or unfocused if pause_on_focus_lost is set
+    pub fn is_paused(&self) -> bool {
+        self.minimized || (self.pause_on_focus_lost && !self.focused)
+    }
+
     /// Updates the engine state by calling it's update_fn.
     /// After the update, a redraw will be requested from the event loop.
     pub fn update(&mut self) {
-        // update the inner state
-        (self.update_fn)(&mut self.state);
+        // update the inner state, unless paused
+        if !self.is_paused() {
+            (self.update_fn)(&mut self.state);
+        }
 
         self.buffer.window().request_redraw();
     }
@@ -178,12 +294,58 @@ where
             }
         }
     }
+
+    pub(crate) fn handle_gesture_events(&mut self, events: impl Iterator<Item = GestureEvent>) {
+        let handlers = &mut self.gesture_events;
+
+        for event in events {
+            for handler in handlers.iter_mut() {
+                (handler)(&mut self.state, &event);
+            }
+        }
+    }
+
+    pub(crate) fn handle_user_event(&mut self, event: &E) {
+        for handler in self.user_events.iter_mut() {
+            (handler)(&mut self.state, event);
+        }
+    }
+
+    pub(crate) fn handle_window_event(&mut self, event: WindowEvent) {
+        match event {
+            WindowEvent::FocusGained => self.focused = true,
+            WindowEvent::FocusLost => self.focused = false,
+            WindowEvent::Minimized => self.minimized = true,
+            WindowEvent::Restored => self.minimized = false,
+            WindowEvent::Resized(width, height) => self.buffer.resize(width, height),
+            _ => (),
+        }
+
+        for handler in self.window_events.iter_mut() {
+            (handler)(&mut self.state, &event);
+        }
+    }
+
+    pub(crate) fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
+    pub(crate) fn handle_action_events(&mut self, input: &WinitInputHelper) {
+        let handlers = &mut self.action_events;
+
+        for event in self.input_map.update(input) {
+            for handler in handlers.iter_mut() {
+                (handler)(&mut self.state, &event);
+            }
+        }
+    }
 }
 
 // Drawing routines
-impl<T> PixelGameEngine<T>
+impl<T, E> PixelGameEngine<T, E>
 where
     T: Sized,
+    E: 'static,
 {
     /// Draw the engine state to a new frame.
     /// The default implementation of this does nothing.
@@ -0,0 +1,438 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::Path,
+    rc::Rc,
+};
+
+use winit::event::VirtualKeyCode;
+use winit_input_helper::WinitInputHelper;
+
+/// A physical input an action can be bound to
+#[derive(Clone, Debug, PartialEq)]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    MouseButton(u8),
+    /// All of the given bindings have to be held at the same time,
+    /// e.g. `LControl + S`
+    Combination(Vec<Binding>),
+}
+
+impl Binding {
+    fn is_held(&self, input: &WinitInputHelper) -> bool {
+        match self {
+            Binding::Key(key) => input.key_held(*key) || input.key_pressed(*key),
+            Binding::MouseButton(button) => {
+                input.mouse_held(*button as usize) || input.mouse_pressed(*button as usize)
+            }
+            Binding::Combination(bindings) => bindings.iter().all(|b| b.is_held(input)),
+        }
+    }
+
+    fn parse(token: &str) -> Option<Binding> {
+        let parts: Vec<&str> = token.split('+').map(str::trim).collect();
+        if parts.len() > 1 {
+            return parts
+                .into_iter()
+                .map(Binding::parse_single)
+                .collect::<Option<Vec<Binding>>>()
+                .map(Binding::Combination);
+        }
+
+        Binding::parse_single(parts[0])
+    }
+
+    fn parse_single(token: &str) -> Option<Binding> {
+        if let Some(button) = token.strip_prefix("Mouse") {
+            if let Ok(button) = button.parse::<u8>() {
+                return Some(Binding::MouseButton(button));
+            }
+        }
+
+        key_code_from_name(token).map(Binding::Key)
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::MouseButton(button) => write!(f, "Mouse{}", button),
+            Binding::Combination(bindings) => {
+                let parts: Vec<String> = bindings.iter().map(|b| b.to_string()).collect();
+                write!(f, "{}", parts.join(" + "))
+            }
+        }
+    }
+}
+
+impl From<VirtualKeyCode> for Binding {
+    fn from(key: VirtualKeyCode) -> Self {
+        Binding::Key(key)
+    }
+}
+
+/// Two bindings driving an axis towards -1.0 and 1.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisBinding {
+    pub negative: Binding,
+    pub positive: Binding,
+}
+
+impl AxisBinding {
+    pub fn new(negative: impl Into<Binding>, positive: impl Into<Binding>) -> Self {
+        Self {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+}
+
+/// Events emitted for named actions once per frame
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionEvent {
+    Pressed(String),
+    Held(String),
+    Released(String),
+    /// The value of an axis changed, ranges from -1.0 to 1.0
+    Axis(String, f32),
+}
+
+#[derive(Default)]
+struct InputMapInner {
+    actions: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    active: HashMap<String, ButtonState>,
+    axis_values: HashMap<String, f32>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ButtonState {
+    pressed: bool,
+    held: bool,
+    released: bool,
+}
+
+/// Maps named actions and axes to physical inputs.
+///
+/// The map is a shared handle, clones refer to the same bindings.
+/// Keep a clone in the game state to query actions or rebind them at runtime.
+#[derive(Clone, Default)]
+pub struct InputMap {
+    inner: Rc<RefCell<InputMapInner>>,
+}
+
+/// Binding management
+impl InputMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding to the given action, keeping existing bindings
+    pub fn bind(&self, action: &str, binding: impl Into<Binding>) -> &Self {
+        self.inner
+            .borrow_mut()
+            .actions
+            .entry(action.to_string())
+            .or_default()
+            .push(binding.into());
+        self
+    }
+
+    /// Adds a negative/positive binding pair to the given axis
+    pub fn bind_axis(&self, axis: &str, binding: AxisBinding) -> &Self {
+        self.inner
+            .borrow_mut()
+            .axes
+            .entry(axis.to_string())
+            .or_default()
+            .push(binding);
+        self
+    }
+
+    /// Replaces all bindings of the given action
+    pub fn rebind(&self, action: &str, bindings: Vec<Binding>) {
+        self.inner
+            .borrow_mut()
+            .actions
+            .insert(action.to_string(), bindings);
+    }
+
+    /// Replaces all bindings of the given axis
+    pub fn rebind_axis(&self, axis: &str, bindings: Vec<AxisBinding>) {
+        self.inner
+            .borrow_mut()
+            .axes
+            .insert(axis.to_string(), bindings);
+    }
+
+    /// Removes the action or axis with the given name
+    pub fn unbind(&self, name: &str) {
+        let mut inner = self.inner.borrow_mut();
+        inner.actions.remove(name);
+        inner.axes.remove(name);
+        inner.active.remove(name);
+        inner.axis_values.remove(name);
+    }
+
+    pub fn bindings(&self, action: &str) -> Vec<Binding> {
+        self.inner
+            .borrow()
+            .actions
+            .get(action)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> Vec<AxisBinding> {
+        self.inner
+            .borrow()
+            .axes
+            .get(axis)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Queries, reflecting the state of the last frame
+impl InputMap {
+    pub fn is_pressed(&self, action: &str) -> bool {
+        self.button_state(action).pressed
+    }
+
+    pub fn is_held(&self, action: &str) -> bool {
+        self.button_state(action).held
+    }
+
+    pub fn is_released(&self, action: &str) -> bool {
+        self.button_state(action).released
+    }
+
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.inner
+            .borrow()
+            .axis_values
+            .get(axis)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn button_state(&self, action: &str) -> ButtonState {
+        self.inner
+            .borrow()
+            .active
+            .get(action)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Evaluates all bindings against the current input state
+    /// and returns the resulting action events
+    pub(crate) fn update(&self, input: &WinitInputHelper) -> Vec<ActionEvent> {
+        let mut events: Vec<ActionEvent> = Vec::new();
+        let inner = &mut *self.inner.borrow_mut();
+
+        for (action, bindings) in inner.actions.iter() {
+            let held = bindings.iter().any(|b| b.is_held(input));
+            let state = inner.active.entry(action.clone()).or_default();
+            let was_held = state.held;
+
+            *state = ButtonState {
+                pressed: held && !was_held,
+                held,
+                released: !held && was_held,
+            };
+
+            if state.pressed {
+                events.push(ActionEvent::Pressed(action.clone()));
+            }
+            if state.held {
+                events.push(ActionEvent::Held(action.clone()));
+            }
+            if state.released {
+                events.push(ActionEvent::Released(action.clone()));
+            }
+        }
+
+        for (axis, bindings) in inner.axes.iter() {
+            let value = bindings
+                .iter()
+                .map(|b| {
+                    let negative = if b.negative.is_held(input) { 1.0 } else { 0.0 };
+                    let positive = if b.positive.is_held(input) { 1.0 } else { 0.0 };
+                    positive - negative
+                })
+                .sum::<f32>()
+                .clamp(-1.0, 1.0);
+
+            let previous = inner.axis_values.insert(axis.clone(), value);
+            if previous.unwrap_or(0.0) != value {
+                events.push(ActionEvent::Axis(axis.clone(), value));
+            }
+        }
+
+        events
+    }
+}
+
+/// Saving and loading
+///
+/// The config format is line based, with bindings separated by commas:
+///
+/// ```text
+/// [actions]
+/// jump = Space, Mouse0
+/// save = LControl + S
+///
+/// [axes]
+/// move_x = A / D, Left / Right
+/// ```
+impl InputMap {
+    pub fn to_config_string(&self) -> String {
+        let inner = self.inner.borrow();
+        let mut config = String::from("[actions]\n");
+
+        for (action, bindings) in inner.actions.iter() {
+            let bindings: Vec<String> = bindings.iter().map(|b| b.to_string()).collect();
+            config.push_str(&format!("{} = {}\n", action, bindings.join(", ")));
+        }
+
+        config.push_str("\n[axes]\n");
+        for (axis, bindings) in inner.axes.iter() {
+            let bindings: Vec<String> = bindings
+                .iter()
+                .map(|b| format!("{} / {}", b.negative, b.positive))
+                .collect();
+            config.push_str(&format!("{} = {}\n", axis, bindings.join(", ")));
+        }
+
+        config
+    }
+
+    pub fn from_config_str(config: &str) -> io::Result<Self> {
+        let map = InputMap::new();
+        let mut in_axes = false;
+
+        for (number, line) in config.lines().enumerate() {
+            let invalid = |message: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, message),
+                )
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line {
+                "[actions]" => in_axes = false,
+                "[axes]" => in_axes = true,
+                _ => {
+                    let mut parts = line.splitn(2, '=');
+                    let name = parts.next().unwrap_or_default().trim();
+                    let bindings = parts.next().ok_or_else(|| invalid("expected '='"))?;
+                    if name.is_empty() {
+                        return Err(invalid("empty action name"));
+                    }
+
+                    for token in bindings.split(',').map(str::trim) {
+                        if in_axes {
+                            let mut pair = token.splitn(2, '/');
+                            let negative = pair.next().and_then(Binding::parse);
+                            let positive = pair.next().and_then(Binding::parse);
+                            match (negative, positive) {
+                                (Some(negative), Some(positive)) => {
+                                    map.bind_axis(name, AxisBinding { negative, positive });
+                                }
+                                _ => return Err(invalid(&format!("invalid axis '{}'", token))),
+                            }
+                        } else {
+                            let binding = Binding::parse(token)
+                                .ok_or_else(|| invalid(&format!("invalid binding '{}'", token)))?;
+                            map.bind(name, binding);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_config_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_config_str(&fs::read_to_string(path)?)
+    }
+}
+
+macro_rules! key_codes {
+    ($($key:ident),* $(,)?) => {
+        fn key_code_from_name(name: &str) -> Option<VirtualKeyCode> {
+            match name {
+                $(stringify!($key) => Some(VirtualKeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+key_codes! {
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0, A, B, C, D, E, F, G, H, I, J, K, L,
+    M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11,
+    F12, F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, Snapshot, Scroll, Pause,
+    Insert, Home, Delete, End, PageDown, PageUp, Left, Up, Right, Down, Back, Return, Space,
+    Compose, Caret, Numlock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7,
+    Numpad8, Numpad9, NumpadAdd, NumpadDivide, NumpadDecimal, NumpadComma, NumpadEnter,
+    NumpadEquals, NumpadMultiply, NumpadSubtract, AbntC1, AbntC2, Apostrophe, Apps, Asterisk, At,
+    Ax, Backslash, Calculator, Capital, Colon, Comma, Convert, Equals, Grave, Kana, Kanji, LAlt,
+    LBracket, LControl, LShift, LWin, Mail, MediaSelect, MediaStop, Minus, Mute, MyComputer,
+    NavigateForward, NavigateBackward, NextTrack, NoConvert, OEM102, Period, PlayPause, Plus, Power,
+    PrevTrack, RAlt, RBracket, RControl, RShift, RWin, Semicolon, Slash, Sleep, Stop, Sysrq, Tab,
+    Underline, Unlabeled, VolumeDown, VolumeUp, Wake, WebBack, WebFavorites, WebForward, WebHome,
+    WebRefresh, WebSearch, WebStop, Yen, Copy, Paste, Cut,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_round_trip() {
+        // Arrange
+        let map = InputMap::new();
+        map.bind("jump", VirtualKeyCode::Space)
+            .bind("jump", Binding::MouseButton(0))
+            .bind(
+                "save",
+                Binding::Combination(vec![
+                    Binding::Key(VirtualKeyCode::LControl),
+                    Binding::Key(VirtualKeyCode::S),
+                ]),
+            )
+            .bind_axis(
+                "move_x",
+                AxisBinding::new(VirtualKeyCode::A, VirtualKeyCode::D),
+            );
+
+        // Act
+        let loaded = InputMap::from_config_str(&map.to_config_string()).unwrap();
+
+        // Assert
+        assert_eq!(map.bindings("jump"), loaded.bindings("jump"));
+        assert_eq!(map.bindings("save"), loaded.bindings("save"));
+        assert_eq!(map.axis_bindings("move_x"), loaded.axis_bindings("move_x"));
+    }
+
+    #[test]
+    fn invalid_binding() {
+        assert!(InputMap::from_config_str("jump = NotAKey").is_err());
+        assert!(InputMap::from_config_str("[axes]\nmove_x = A").is_err());
+        assert!(InputMap::from_config_str("= Space").is_err());
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use crate::pixel::Pixel;
+
+/// Higher level mouse events, positions are in buffer pixels
+#[derive(Clone, Debug, PartialEq)]
+pub enum GestureEvent {
+    /// A press and release without dragging.
+    /// `count` is 2 for a double-click, 3 for a triple-click and so on.
+    Click {
+        button: u8,
+        position: Pixel,
+        count: u32,
+    },
+    DragStart {
+        button: u8,
+        origin: Pixel,
+    },
+    /// `delta` is the movement since the last drag event
+    DragMove {
+        button: u8,
+        origin: Pixel,
+        position: Pixel,
+        delta: (i32, i32),
+    },
+    DragEnd {
+        button: u8,
+        origin: Pixel,
+        position: Pixel,
+    },
+    /// A button was held in place for the long press duration.
+    /// No click is emitted on release after a long press.
+    LongPress {
+        button: u8,
+        position: Pixel,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GestureConfig {
+    /// Distance in pixels the cursor has to move while pressed to start a drag
+    pub drag_threshold: u32,
+    /// Maximum time between clicks to count as a double or triple click
+    pub multi_click_interval: Duration,
+    pub long_press_duration: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            drag_threshold: 4,
+            multi_click_interval: Duration::from_millis(400),
+            long_press_duration: Duration::from_millis(600),
+        }
+    }
+}
+
+struct Press {
+    origin: Pixel,
+    time: Instant,
+    dragging: bool,
+    long_pressed: bool,
+}
+
+struct LastClick {
+    position: Pixel,
+    time: Instant,
+    count: u32,
+}
+
+/// State machine turning raw button and cursor input into gesture events
+pub(crate) struct GestureDetector {
+    config: GestureConfig,
+    position: Pixel,
+    /// Sorted by button, so simultaneous events are emitted in a stable order
+    presses: BTreeMap<u8, Press>,
+    last_clicks: HashMap<u8, LastClick>,
+}
+
+fn distance_squared(a: Pixel, b: Pixel) -> u64 {
+    let dx = a.x() as i64 - b.x() as i64;
+    let dy = a.y() as i64 - b.y() as i64;
+    (dx * dx + dy * dy) as u64
+}
+
+impl GestureDetector {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            position: Pixel((0, 0)),
+            presses: BTreeMap::new(),
+            last_clicks: HashMap::new(),
+        }
+    }
+
+    fn exceeds_threshold(&self, a: Pixel, b: Pixel) -> bool {
+        let threshold = self.config.drag_threshold as u64;
+        distance_squared(a, b) > threshold * threshold
+    }
+
+    pub fn press(&mut self, button: u8, now: Instant) {
+        self.presses.insert(
+            button,
+            Press {
+                origin: self.position,
+                time: now,
+                dragging: false,
+                long_pressed: false,
+            },
+        );
+    }
+
+    pub fn release(&mut self, button: u8, now: Instant) -> Vec<GestureEvent> {
+        let mut events: Vec<GestureEvent> = Vec::new();
+        let press = match self.presses.remove(&button) {
+            Some(press) => press,
+            None => return events,
+        };
+
+        if press.dragging {
+            events.push(GestureEvent::DragEnd {
+                button,
+                origin: press.origin,
+                position: self.position,
+            });
+        } else if !press.long_pressed {
+            let count = match self.last_clicks.get(&button) {
+                Some(last)
+                    if now.duration_since(last.time) <= self.config.multi_click_interval
+                        && !self.exceeds_threshold(last.position, self.position) =>
+                {
+                    last.count + 1
+                }
+                _ => 1,
+            };
+
+            self.last_clicks.insert(
+                button,
+                LastClick {
+                    position: self.position,
+                    time: now,
+                    count,
+                },
+            );
+
+            events.push(GestureEvent::Click {
+                button,
+                position: self.position,
+                count,
+            });
+        }
+
+        events
+    }
+
+    pub fn move_to(&mut self, position: Pixel) -> Vec<GestureEvent> {
+        let mut events: Vec<GestureEvent> = Vec::new();
+        let previous = self.position;
+        self.position = position;
+
+        if previous == position {
+            return events;
+        }
+
+        let buttons: Vec<u8> = self.presses.keys().copied().collect();
+
+        for button in buttons {
+            let origin = self.presses[&button].origin;
+            let start_drag =
+                !self.presses[&button].dragging && self.exceeds_threshold(origin, position);
+
+            let press = self.presses.get_mut(&button).unwrap();
+            if start_drag {
+                press.dragging = true;
+                events.push(GestureEvent::DragStart { button, origin });
+            }
+
+            if press.dragging {
+                // the first move of a drag covers the distance from its origin
+                let from = if start_drag { origin } else { previous };
+                events.push(GestureEvent::DragMove {
+                    button,
+                    origin,
+                    position,
+                    delta: (
+                        position.x() as i32 - from.x() as i32,
+                        position.y() as i32 - from.y() as i32,
+                    ),
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Emits long presses for buttons held in place long enough
+    pub fn tick(&mut self, now: Instant) -> Vec<GestureEvent> {
+        let mut events: Vec<GestureEvent> = Vec::new();
+        let position = self.position;
+        let duration = self.config.long_press_duration;
+
+        for (button, press) in self.presses.iter_mut() {
+            if !press.dragging && !press.long_pressed && now.duration_since(press.time) >= duration
+            {
+                press.long_pressed = true;
+                events.push(GestureEvent::LongPress {
+                    button: *button,
+                    position,
+                });
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn click_and_double_click() {
+        // Arrange
+        let mut detector = GestureDetector::new(GestureConfig::default());
+        let start = Instant::now();
+        detector.move_to((10, 10).into());
+
+        // Act
+        detector.press(0, start);
+        let first = detector.release(0, start + Duration::from_millis(50));
+        detector.press(0, start + Duration::from_millis(100));
+        let second = detector.release(0, start + Duration::from_millis(150));
+        detector.press(0, start + Duration::from_secs(2));
+        let third = detector.release(0, start + Duration::from_secs(2));
+
+        // Assert
+        let click = |count| GestureEvent::Click {
+            button: 0,
+            position: (10, 10).into(),
+            count,
+        };
+        assert_eq!(first, vec![click(1)]);
+        assert_eq!(second, vec![click(2)]);
+        assert_eq!(third, vec![click(1)]);
+    }
+
+    #[test]
+    fn drag_beyond_threshold() {
+        // Arrange
+        let mut detector = GestureDetector::new(GestureConfig::default());
+        let start = Instant::now();
+        detector.move_to((10, 10).into());
+        detector.press(0, start);
+
+        // Act
+        let small = detector.move_to((12, 10).into());
+        let started = detector.move_to((20, 10).into());
+        let moved = detector.move_to((20, 15).into());
+        let ended = detector.release(0, start);
+
+        // Assert
+        assert!(small.is_empty());
+        assert_eq!(
+            started,
+            vec![
+                GestureEvent::DragStart {
+                    button: 0,
+                    origin: (10, 10).into()
+                },
+                GestureEvent::DragMove {
+                    button: 0,
+                    origin: (10, 10).into(),
+                    position: (20, 10).into(),
+                    delta: (10, 0)
+                }
+            ]
+        );
+        assert_eq!(
+            moved,
+            vec![GestureEvent::DragMove {
+                button: 0,
+                origin: (10, 10).into(),
+                position: (20, 15).into(),
+                delta: (0, 5)
+            }]
+        );
+        assert_eq!(
+            ended,
+            vec![GestureEvent::DragEnd {
+                button: 0,
+                origin: (10, 10).into(),
+                position: (20, 15).into()
+            }]
+        );
+    }
+
+    #[test]
+    fn long_press_suppresses_click() {
+        // Arrange
+        let mut detector = GestureDetector::new(GestureConfig::default());
+        let start = Instant::now();
+        detector.press(1, start);
+
+        // Act
+        let early = detector.tick(start + Duration::from_millis(100));
+        let late = detector.tick(start + Duration::from_secs(1));
+        let released = detector.release(1, start + Duration::from_secs(1));
+
+        // Assert
+        assert!(early.is_empty());
+        assert_eq!(
+            late,
+            vec![GestureEvent::LongPress {
+                button: 1,
+                position: (0, 0).into()
+            }]
+        );
+        assert!(released.is_empty());
+    }
+
+    #[test]
+    fn long_presses_are_ordered_by_button() {
+        // Arrange
+        let mut detector = GestureDetector::new(GestureConfig::default());
+        let start = Instant::now();
+        for button in [3, 0, 2, 1].iter() {
+            detector.press(*button, start);
+        }
+
+        // Act
+        let events = detector.tick(start + Duration::from_secs(1));
+
+        // Assert
+        let buttons: Vec<u8> = events
+            .iter()
+            .map(|event| match event {
+                GestureEvent::LongPress { button, .. } => *button,
+                _ => panic!("unexpected event {:?}", event),
+            })
+            .collect();
+        assert_eq!(buttons, vec![0, 1, 2, 3]);
+    }
+}
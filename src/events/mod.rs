@@ -1,11 +1,13 @@
 use log::error;
 use winit::{
     event::{Event, VirtualKeyCode},
-    event_loop::ControlFlow,
+    event_loop::{ControlFlow, EventLoopProxy},
 };
 use winit_input_helper::WinitInputHelper;
 
-use crate::engine::PixelGameEngineBuilder;
+use std::time::Instant;
+
+use crate::{engine::PixelGameEngineBuilder, pixel::Pixel};
 
 mod key_event;
 pub use key_event::KeyEvent;
@@ -13,20 +15,87 @@ pub use key_event::KeyEvent;
 mod mouse_event;
 pub use mouse_event::MouseEvent;
 
-pub struct EventLoop;
+mod action;
+pub use action::{ActionEvent, AxisBinding, Binding, InputMap};
+
+mod window_event;
+pub use window_event::WindowEvent;
+
+mod gesture;
+pub(crate) use gesture::GestureDetector;
+pub use gesture::{GestureConfig, GestureEvent};
+
+/// Sends user events into a running engine, can be cloned and moved to other threads
+pub struct UserEventProxy<E: 'static> {
+    proxy: EventLoopProxy<E>,
+}
+
+impl<E: 'static> UserEventProxy<E> {
+    /// Sends the event to the engine's user event listeners.
+    /// If the event loop has already exited, the event is handed back.
+    pub fn send(&self, event: E) -> Result<(), E> {
+        self.proxy.send_event(event).map_err(|closed| closed.0)
+    }
+}
+
+impl<E: 'static> Clone for UserEventProxy<E> {
+    fn clone(&self) -> Self {
+        Self {
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+
+pub struct EventLoop<E: 'static = ()> {
+    event_loop: winit::event_loop::EventLoop<E>,
+}
+
+impl<E: 'static> EventLoop<E> {
+    pub fn new() -> Self {
+        Self {
+            event_loop: winit::event_loop::EventLoop::with_user_event(),
+        }
+    }
+
+    /// Creates a proxy to send user events from other threads
+    pub fn create_proxy(&self) -> UserEventProxy<E> {
+        UserEventProxy {
+            proxy: self.event_loop.create_proxy(),
+        }
+    }
+
+    pub fn build_and_run<T: Sized + 'static>(builder: PixelGameEngineBuilder<T, E>) -> ! {
+        Self::new().run(builder)
+    }
 
-impl EventLoop {
-    pub fn build_and_run<T: Sized + 'static>(builder: PixelGameEngineBuilder<T>) -> ! {
-        // initialize input helper and event loop
+    pub fn run<T: Sized + 'static>(self, builder: PixelGameEngineBuilder<T, E>) -> ! {
+        // initialize input helper
         let mut input = WinitInputHelper::new();
-        let event_loop = winit::event_loop::EventLoop::new();
+        let event_loop = self.event_loop;
 
         // create an engine from the builder
         let mut engine = builder.build(&event_loop);
 
         // run the winit loop
         event_loop.run(move |event, _, control_flow| {
+            // handle events sent through a proxy
+            if let Event::UserEvent(user_event) = &event {
+                engine.handle_user_event(user_event);
+            }
+
+            // emit and handle window events
+            if let Event::WindowEvent { event, .. } = &event {
+                for window_event in emit_window_events(event, engine.is_minimized()) {
+                    engine.handle_window_event(window_event);
+                }
+            }
+
             if let Event::RedrawRequested(_) = event {
+                // a minimized window has no surface to render to
+                if engine.is_minimized() {
+                    return;
+                }
+
                 if engine
                     .draw_frame()
                     .map_err(|e| error!("pixels.render() failed: {}", e))
@@ -44,6 +113,15 @@ impl EventLoop {
                     *control_flow = ControlFlow::Exit;
                     return;
                 }
+
+                // evaluate action bindings, once per frame
+                engine.handle_action_events(&input);
+
+                // emit and handle mouse and gesture events, once per frame
+                engine.handle_mouse_events(emit_mouse_events(&input));
+                let position = input.mouse().map(|p| engine.buffer.window_to_pixel(p));
+                let gestures = emit_gesture_events(&input, &mut engine.gestures, position);
+                engine.handle_gesture_events(gestures);
             }
 
             // emit and handle key events
@@ -57,6 +135,12 @@ impl EventLoop {
     }
 }
 
+impl<E: 'static> Default for EventLoop<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 fn iter_key_codes() -> impl Iterator<Item = VirtualKeyCode> {
     vec![
         VirtualKeyCode::W,
@@ -87,3 +171,115 @@ fn emit_key_events(input: &WinitInputHelper) -> impl Iterator<Item = KeyEvent> {
 
     events.into_iter()
 }
+
+fn iter_mouse_buttons() -> impl Iterator<Item = u8> {
+    // left, right and middle
+    0..3
+}
+
+fn emit_mouse_events(input: &WinitInputHelper) -> impl Iterator<Item = MouseEvent> {
+    let mut events: Vec<MouseEvent> = Vec::new();
+    iter_mouse_buttons().for_each(|b| {
+        if input.mouse_pressed(b as usize) {
+            events.push(MouseEvent::ButtonPressed(b));
+        };
+        if input.mouse_held(b as usize) {
+            events.push(MouseEvent::ButtonHeld(b));
+        };
+        if input.mouse_released(b as usize) {
+            events.push(MouseEvent::ButtonReleased(b));
+        };
+    });
+
+    if input.mouse_diff() != (0.0, 0.0) {
+        events.push(MouseEvent::Moved);
+    }
+
+    events.into_iter()
+}
+
+fn emit_gesture_events(
+    input: &WinitInputHelper,
+    detector: &mut GestureDetector,
+    position: Option<Pixel>,
+) -> impl Iterator<Item = GestureEvent> {
+    let now = Instant::now();
+    let mut events: Vec<GestureEvent> = Vec::new();
+
+    if let Some(position) = position {
+        events.extend(detector.move_to(position));
+    }
+
+    iter_mouse_buttons().for_each(|b| {
+        if input.mouse_pressed(b as usize) {
+            detector.press(b, now);
+        }
+        if input.mouse_released(b as usize) {
+            events.extend(detector.release(b, now));
+        }
+    });
+
+    events.extend(detector.tick(now));
+
+    events.into_iter()
+}
+
+fn emit_window_events(
+    event: &winit::event::WindowEvent,
+    is_minimized: bool,
+) -> impl Iterator<Item = WindowEvent> {
+    use winit::event::WindowEvent as WinitEvent;
+
+    let mut events: Vec<WindowEvent> = Vec::new();
+    match event {
+        WinitEvent::Focused(true) => events.push(WindowEvent::FocusGained),
+        WinitEvent::Focused(false) => events.push(WindowEvent::FocusLost),
+        WinitEvent::Resized(size) => {
+            // winit reports minimizing as a resize to zero
+            let is_zero = size.width == 0 || size.height == 0;
+
+            if is_zero && !is_minimized {
+                events.push(WindowEvent::Minimized);
+            } else if !is_zero {
+                if is_minimized {
+                    events.push(WindowEvent::Restored);
+                }
+                events.push(WindowEvent::Resized(size.width, size.height));
+            }
+        }
+        WinitEvent::HoveredFile(path) => events.push(WindowEvent::FileHovered(path.clone())),
+        WinitEvent::HoveredFileCancelled => events.push(WindowEvent::FileHoverCancelled),
+        WinitEvent::DroppedFile(path) => events.push(WindowEvent::FileDropped(path.clone())),
+        _ => (),
+    }
+
+    events.into_iter()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use winit::{dpi::PhysicalSize, event::WindowEvent as WinitEvent};
+
+    #[test]
+    fn minimize_and_restore() {
+        // Arrange
+        let resized = |width, height| WinitEvent::Resized(PhysicalSize::new(width, height));
+        let emit = |event, minimized| emit_window_events(&event, minimized).collect::<Vec<_>>();
+
+        // Act
+        let minimized = emit(resized(0, 0), false);
+        let still_minimized = emit(resized(0, 0), true);
+        let restored = emit(resized(800, 600), true);
+        let resized = emit(resized(640, 480), false);
+
+        // Assert
+        assert_eq!(minimized, vec![WindowEvent::Minimized]);
+        assert!(still_minimized.is_empty());
+        assert_eq!(
+            restored,
+            vec![WindowEvent::Restored, WindowEvent::Resized(800, 600)]
+        );
+        assert_eq!(resized, vec![WindowEvent::Resized(640, 480)]);
+    }
+}
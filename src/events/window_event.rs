@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowEvent {
+    FocusGained,
+    FocusLost,
+    /// The new inner size of the window in physical pixels
+    Resized(u32, u32),
+    Minimized,
+    Restored,
+    FileHovered(PathBuf),
+    FileHoverCancelled,
+    FileDropped(PathBuf),
+}
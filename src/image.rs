@@ -0,0 +1,151 @@
+use crate::{
+    color::Color,
+    draw::Drawable,
+    mask::{Connectivity, Mask},
+    pixel::Pixel,
+};
+
+/// A small image, e.g. a sprite, a tile of a pattern or a sheet of glyphs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    colors: Vec<Color>,
+}
+
+impl Image {
+    /// `colors` are the rows of the image, from top to bottom.
+    /// Returns None, if a size is zero or the count of colors does not match the size.
+    pub fn new(width: u32, height: u32, colors: Vec<Color>) -> Option<Self> {
+        if width == 0 || height == 0 || colors.len() != width as usize * height as usize {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height,
+            colors,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The rows of the image, from top to bottom
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    fn index(&self, pixel: Pixel) -> Option<usize> {
+        if pixel.x() < self.width && pixel.y() < self.height {
+            Some(pixel.y() as usize * self.width as usize + pixel.x() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The color of the pixel, None if it is outside of the image
+    pub fn color_at(&self, pixel: Pixel) -> Option<Color> {
+        self.index(pixel).map(|index| self.colors[index])
+    }
+
+    /// Sets the color of the pixel, pixels outside of the image are ignored
+    pub fn set_color(&mut self, pixel: Pixel, color: impl Into<Color>) {
+        if let Some(index) = self.index(pixel) {
+            self.colors[index] = color.into();
+        }
+    }
+
+    /// Selects the area connected to the seed, in which the predicate holds for every pixel
+    pub fn select_region(
+        &self,
+        seed: Pixel,
+        connectivity: Connectivity,
+        mut predicate: impl FnMut(Pixel, Color) -> bool,
+    ) -> Mask {
+        Mask::flood(
+            self.width,
+            self.height,
+            seed,
+            connectivity,
+            |pixel| match self.color_at(pixel) {
+                Some(color) => predicate(pixel, color),
+                None => false,
+            },
+        )
+    }
+
+    /// Bucket fill, replacing the area around the seed, whose colors differ from the seed color
+    /// by at most `tolerance` in each channel
+    pub fn flood_fill(
+        &mut self,
+        seed: Pixel,
+        color: impl Into<Color>,
+        tolerance: u8,
+        connectivity: Connectivity,
+    ) {
+        let target = match self.color_at(seed) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let region = self.select_region(seed, connectivity, |_, color| {
+            target.is_close_to(color, tolerance)
+        });
+        let color = color.into();
+        region
+            .pixels()
+            .for_each(|pixel| self.set_color(pixel, color));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validates_size() {
+        // Arrange
+        let mut image = Image::new(2, 1, vec![Color::RED, Color::BLUE]).unwrap();
+
+        // Act
+        image.set_color((0, 0).into(), Color::GREEN);
+        image.set_color((2, 0).into(), Color::GREEN);
+
+        // Assert
+        assert_eq!(image.color_at((0, 0).into()), Some(Color::GREEN));
+        assert_eq!(image.color_at((1, 0).into()), Some(Color::BLUE));
+        assert_eq!(image.color_at((0, 1).into()), None);
+        assert!(Image::new(2, 2, vec![Color::RED]).is_none());
+        assert!(Image::new(0, 0, vec![]).is_none());
+    }
+
+    #[test]
+    fn flood_fill() {
+        // Arrange
+        let (o, x) = (Color::WHITE, Color::BLACK);
+        #[rustfmt::skip]
+        let colors = vec![
+            o, o, x, o,
+            o, x, o, o,
+            x, o, o, o,
+        ];
+        let mut four = Image::new(4, 3, colors).unwrap();
+        let mut eight = four.clone();
+
+        // Act
+        four.flood_fill((3, 2).into(), Color::RED, 0, Connectivity::Four);
+        eight.flood_fill((3, 2).into(), Color::RED, 0, Connectivity::Eight);
+
+        // Assert
+        let red = |image: &Image| image.colors().iter().filter(|c| **c == Color::RED).count();
+        assert_eq!(red(&four), 6);
+        assert_eq!(four.color_at((0, 0).into()), Some(Color::WHITE));
+        assert_eq!(red(&eight), 9);
+    }
+}
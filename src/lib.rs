@@ -6,6 +6,9 @@ pub mod color;
 pub mod draw;
 pub mod engine;
 pub mod events;
+pub mod image;
+pub mod mask;
+pub mod paint;
 pub mod pixel;
 pub mod primitives;
 pub mod text;
@@ -0,0 +1,255 @@
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+    primitives::{Span, SpanPixels},
+};
+
+/// Which neighbours of a pixel are connected to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Left, right, above and below
+    Four,
+    /// Also the diagonal neighbours
+    Eight,
+}
+
+/// A set of pixels inside of a `width` by `height` area, e.g. a selected region.
+/// It can be drawn or filled like any other shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    width: u32,
+    height: u32,
+    covered: Vec<bool>,
+}
+
+impl Mask {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            covered: vec![false; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, pixel: Pixel) -> bool {
+        match self.index(pixel.x(), pixel.y()) {
+            Some(index) => self.covered[index],
+            None => false,
+        }
+    }
+
+    /// Adds the pixel, pixels outside of the mask area are ignored
+    pub fn insert(&mut self, pixel: Pixel) {
+        if let Some(index) = self.index(pixel.x(), pixel.y()) {
+            self.covered[index] = true;
+        }
+    }
+
+    /// The number of covered pixels
+    pub fn len(&self) -> usize {
+        self.covered.iter().filter(|c| **c).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.covered.contains(&true)
+    }
+
+    /// Grows the covered pixels by `radius` in every direction, rounding the corners.
+    /// A radius of 1 adds all eight neighbours.
+    pub fn dilate(&self, radius: u32) -> Mask {
+        let mut dilated = self.clone();
+        let r = radius as i64;
+        for y in 0..self.height as i64 {
+            for x in 0..self.width as i64 {
+                if !self.covered[(y * self.width as i64 + x) as usize] {
+                    continue;
+                }
+
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if dx * dx + dy * dy <= r * r + r && nx >= 0 && ny >= 0 {
+                            dilated.insert(Pixel((nx as u32, ny as u32)));
+                        }
+                    }
+                }
+            }
+        }
+
+        dilated
+    }
+
+    /// Selects the area connected to the seed, in which `matches` holds for every pixel.
+    ///
+    /// Uses a scanline fill, which marks whole runs of a row at once
+    /// and only remembers one seed per run in the rows above and below.
+    pub fn flood(
+        width: u32,
+        height: u32,
+        seed: Pixel,
+        connectivity: Connectivity,
+        mut matches: impl FnMut(Pixel) -> bool,
+    ) -> Self {
+        let mut mask = Mask::new(width, height);
+        if mask.index(seed.x(), seed.y()).is_none() || !matches(seed) {
+            return mask;
+        }
+
+        let mut seeds = vec![(seed.x(), seed.y())];
+        while let Some((x, y)) = seeds.pop() {
+            if mask.contains(Pixel((x, y))) {
+                continue;
+            }
+
+            let mut open = |mask: &Mask, x: u32, y: u32| {
+                !mask.contains(Pixel((x, y))) && matches(Pixel((x, y)))
+            };
+
+            let mut left = x;
+            while left > 0 && open(&mask, left - 1, y) {
+                left -= 1;
+            }
+            let mut right = x;
+            while right + 1 < width && open(&mask, right + 1, y) {
+                right += 1;
+            }
+
+            for x in left..=right {
+                mask.insert(Pixel((x, y)));
+            }
+
+            // diagonal neighbours extend the range checked in the rows above and below
+            let (from, to) = match connectivity {
+                Connectivity::Four => (left, right),
+                Connectivity::Eight => (left.saturating_sub(1), (right + 1).min(width - 1)),
+            };
+
+            let rows = [y.checked_sub(1), Some(y + 1).filter(|y| *y < height)];
+            for row in rows.iter().flatten().copied() {
+                let mut in_run = false;
+                for x in from..=to {
+                    if open(&mask, x, row) {
+                        if !in_run {
+                            seeds.push((x, row));
+                        }
+                        in_run = true;
+                    } else {
+                        in_run = false;
+                    }
+                }
+            }
+        }
+
+        mask
+    }
+}
+
+impl Drawable for Mask {
+    type IntoIter = SpanPixels<std::vec::IntoIter<Span>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+/// The runs of covered pixels in each row
+impl SpanDrawable for Mask {
+    type Spans = std::vec::IntoIter<Span>;
+
+    fn spans(&self) -> Self::Spans {
+        let mut spans = Vec::new();
+        if self.width == 0 {
+            return spans.into_iter();
+        }
+
+        for (y, row) in self.covered.chunks_exact(self.width as usize).enumerate() {
+            let mut start = None;
+            for (x, covered) in row.iter().chain(std::iter::once(&false)).enumerate() {
+                match (start, covered) {
+                    (None, true) => start = Some(x),
+                    (Some(from), false) => {
+                        spans.push(Span::new(y as u32, from as u32, x as u32));
+                        start = None;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        spans.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Parses rows of `#` for walls and `.` for open pixels
+    fn grid<'a>(rows: &'a [&'a str]) -> impl Fn(Pixel) -> bool + 'a {
+        move |p: Pixel| rows[p.y() as usize].as_bytes()[p.x() as usize] == b'.'
+    }
+
+    #[test]
+    fn connectivity() {
+        // Arrange
+        let rows = ["..#..", "..#..", "##...", ".#...", "#...."];
+
+        // Act
+        let four = Mask::flood(5, 5, (0, 0).into(), Connectivity::Four, grid(&rows));
+        let eight = Mask::flood(5, 5, (4, 4).into(), Connectivity::Eight, grid(&rows));
+
+        // Assert
+        assert_eq!(four.len(), 4);
+        assert!(!four.contains((3, 0).into()));
+        assert!(eight.contains((0, 3).into()));
+        assert!(eight.contains((0, 0).into()));
+    }
+
+    #[test]
+    fn fills_around_obstacles() {
+        // Arrange, a u-shape, which needs seeds going back up
+        let rows = [".#.#.", ".#.#.", ".#.#.", ".....", "#####"];
+
+        // Act
+        let mask = Mask::flood(5, 5, (0, 0).into(), Connectivity::Four, grid(&rows));
+        let spans: Vec<Span> = mask.spans().collect();
+
+        // Assert
+        assert_eq!(mask.len(), 14);
+        assert_eq!(spans[0], Span::new(0, 0, 1));
+        assert_eq!(spans[spans.len() - 1], Span::new(3, 0, 5));
+    }
+
+    #[test]
+    fn dilate() {
+        // Arrange
+        let mut mask = Mask::new(7, 7);
+        mask.insert((3, 3).into());
+
+        // Act
+        let one = mask.dilate(1);
+        let two = mask.dilate(2);
+
+        // Assert
+        assert_eq!(one.len(), 9);
+        assert_eq!(two.len(), 21);
+        assert!(!two.contains((1, 1).into()));
+        assert_eq!(mask.dilate(0), mask);
+    }
+}
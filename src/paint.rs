@@ -0,0 +1,245 @@
+use crate::{color::Color, image::Image, pixel::Pixel};
+
+/// Decides the color of each pixel of a filled shape
+pub trait Paint {
+    fn color_at(&self, pixel: Pixel) -> Color;
+}
+
+/// A solid color
+impl Paint for Color {
+    fn color_at(&self, _: Pixel) -> Color {
+        *self
+    }
+}
+
+impl<P: Paint> Paint for &P {
+    fn color_at(&self, pixel: Pixel) -> Color {
+        (*self).color_at(pixel)
+    }
+}
+
+/// The center of the pixel, gradients are sampled there
+fn center(pixel: Pixel) -> (f32, f32) {
+    (pixel.x() as f32 + 0.5, pixel.y() as f32 + 0.5)
+}
+
+/// Colors at offsets from 0.0 to 1.0, sorted by offset
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Stops(Vec<(f32, Color)>);
+
+impl Stops {
+    fn add(&mut self, offset: f32, color: Color) {
+        let index = self.0.partition_point(|stop| stop.0 <= offset);
+        self.0.insert(index, (offset, color));
+    }
+
+    /// Interpolates between the surrounding stops, before the first and after the last
+    /// stop their color is kept
+    fn color_at(&self, offset: f32) -> Color {
+        let index = self.0.partition_point(|stop| stop.0 <= offset);
+        match (self.0.get(index.wrapping_sub(1)), self.0.get(index)) {
+            (Some(&(from, from_color)), Some(&(to, to_color))) => {
+                from_color.lerp(to_color, (offset - from) / (to - from))
+            }
+            (Some(&(_, color)), None) | (None, Some(&(_, color))) => color,
+            (None, None) => Color::TRANSPARENT,
+        }
+    }
+}
+
+/// Blends the stops along the line from `from` to `to`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    stops: Stops,
+}
+
+impl LinearGradient {
+    pub fn new(from: (f32, f32), to: (f32, f32)) -> Self {
+        Self {
+            from,
+            to,
+            stops: Stops::default(),
+        }
+    }
+
+    /// Adds a color at the offset, where 0.0 is `from` and 1.0 is `to`
+    pub fn with_stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        self.stops.add(offset, color.into());
+        self
+    }
+}
+
+impl Paint for LinearGradient {
+    fn color_at(&self, pixel: Pixel) -> Color {
+        let (x, y) = center(pixel);
+        let (dx, dy) = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        let length_squared = dx * dx + dy * dy;
+        let offset = if length_squared == 0.0 {
+            0.0
+        } else {
+            ((x - self.from.0) * dx + (y - self.from.1) * dy) / length_squared
+        };
+
+        self.stops.color_at(offset)
+    }
+}
+
+/// Blends the stops from the center outwards to the radius
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+    pub center: (f32, f32),
+    pub radius: f32,
+    stops: Stops,
+}
+
+impl RadialGradient {
+    pub fn new(center: (f32, f32), radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+            stops: Stops::default(),
+        }
+    }
+
+    /// Adds a color at the offset, where 0.0 is the center and 1.0 is the radius
+    pub fn with_stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        self.stops.add(offset, color.into());
+        self
+    }
+}
+
+impl Paint for RadialGradient {
+    fn color_at(&self, pixel: Pixel) -> Color {
+        let (x, y) = center(pixel);
+        let distance = (x - self.center.0).hypot(y - self.center.1);
+        let offset = if self.radius == 0.0 {
+            1.0
+        } else {
+            distance / self.radius
+        };
+
+        self.stops.color_at(offset)
+    }
+}
+
+/// Thresholds of the 4x4 Bayer matrix
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Mixes two colors with an ordered dither pattern, without blending them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dither {
+    pub background: Color,
+    pub foreground: Color,
+    /// How many pixels show the foreground, from 0.0 to 1.0 in steps of 1/16
+    pub amount: f32,
+}
+
+impl Dither {
+    pub fn new(background: impl Into<Color>, foreground: impl Into<Color>, amount: f32) -> Self {
+        Self {
+            background: background.into(),
+            foreground: foreground.into(),
+            amount,
+        }
+    }
+
+    /// Whether the pixel shows the foreground for the given amount
+    pub fn is_foreground(pixel: Pixel, amount: f32) -> bool {
+        let threshold = BAYER[pixel.y() as usize % 4][pixel.x() as usize % 4];
+        amount * 16.0 > threshold as f32 + 0.5
+    }
+}
+
+impl Paint for Dither {
+    fn color_at(&self, pixel: Pixel) -> Color {
+        if Dither::is_foreground(pixel, self.amount) {
+            self.foreground
+        } else {
+            self.background
+        }
+    }
+}
+
+/// A small image repeated in both directions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    image: Image,
+    offset: Pixel,
+}
+
+impl Pattern {
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            offset: Pixel((0, 0)),
+        }
+    }
+
+    /// Moves the tiles, so they line up with the drawn shape instead of the buffer
+    pub fn with_offset(mut self, offset: Pixel) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Paint for Pattern {
+    fn color_at(&self, pixel: Pixel) -> Color {
+        let (width, height) = (self.image.width() as i64, self.image.height() as i64);
+        let x = (pixel.x() as i64 - self.offset.x() as i64).rem_euclid(width);
+        let y = (pixel.y() as i64 - self.offset.y() as i64).rem_euclid(height);
+
+        self.image.colors()[(y * width + x) as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gradient_stops() {
+        // Arrange
+        let gradient = LinearGradient::new((0.0, 0.0), (10.0, 0.0))
+            .with_stop(1.0, Color::WHITE)
+            .with_stop(0.0, Color::BLACK)
+            .with_stop(0.5, Color::RED);
+        let radial = RadialGradient::new((0.0, 0.0), 10.0)
+            .with_stop(0.0, Color::WHITE)
+            .with_stop(1.0, Color::BLACK);
+
+        // Act, Assert
+        assert_eq!(gradient.color_at((2, 0).into()), Color::rgb(128, 0, 0));
+        assert_eq!(gradient.color_at((20, 5).into()), Color::WHITE);
+        assert_eq!(radial.color_at((50, 50).into()), Color::BLACK);
+    }
+
+    #[test]
+    fn dither_amount() {
+        for steps in 0..=16 {
+            // Arrange
+            let dither = Dither::new(Color::BLACK, Color::WHITE, steps as f32 / 16.0);
+
+            // Act
+            let foreground = (0..4)
+                .flat_map(|y| (0..4).map(move |x| Pixel((x, y))))
+                .filter(|p| dither.color_at(*p) == Color::WHITE)
+                .count();
+
+            // Assert
+            assert_eq!(foreground, steps);
+        }
+    }
+
+    #[test]
+    fn pattern_repeats() {
+        // Arrange
+        let image = Image::new(2, 1, vec![Color::RED, Color::BLUE]).unwrap();
+        let checker = Pattern::new(image).with_offset((1, 0).into());
+
+        // Act, Assert
+        assert_eq!(checker.color_at((1, 0).into()), Color::RED);
+        assert_eq!(checker.color_at((0, 7).into()), Color::BLUE);
+    }
+}
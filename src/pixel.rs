@@ -1,6 +1,6 @@
 use std::ops::{Add, Sub};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Pixel(pub (u32, u32));
 
 impl Pixel {
@@ -30,8 +30,8 @@ impl Sub<Pixel> for Pixel {
     type Output = Pixel;
 
     fn sub(self, rhs: Pixel) -> Self::Output {
-        let x = self.x().checked_sub(rhs.x()).unwrap_or(0);
-        let y = self.y().checked_sub(rhs.y()).unwrap_or(0);
+        let x = self.x().saturating_sub(rhs.x());
+        let y = self.y().saturating_sub(rhs.y());
 
         Pixel((x, y))
     }
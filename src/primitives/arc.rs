@@ -0,0 +1,271 @@
+use std::f32::consts::TAU;
+
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    ellipse::{Ellipse, EllipseIter, EllipseSpans},
+    line::LineIter,
+    span::{ExtentSpans, Span, SpanPixels},
+};
+
+/// The angles between start and end, turning clockwise
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Sector {
+    start: f32,
+    sweep: f32,
+}
+
+impl Sector {
+    pub(crate) fn new(start: f32, end: f32) -> Self {
+        let sweep = if end - start >= TAU {
+            TAU
+        } else {
+            (end - start).rem_euclid(TAU)
+        };
+
+        Self { start, sweep }
+    }
+
+    pub(crate) fn contains(&self, center: Pixel, x: u32, y: u32) -> bool {
+        let dx = x as f32 - center.x() as f32;
+        let dy = y as f32 - center.y() as f32;
+        if self.sweep >= TAU || (dx == 0.0 && dy == 0.0) {
+            return true;
+        }
+
+        (dy.atan2(dx) - self.start).rem_euclid(TAU) <= self.sweep
+    }
+}
+
+/// A part of the outline of an ellipse, for circles see [`CircleArc`](super::CircleArc).
+///
+/// Angles are in radians around the center, starting at the positive x axis.
+/// As y points down, they turn clockwise on screen.
+/// The arc covers the angles from `start` to `end`,
+/// an `end` of at least `start + TAU` draws the whole outline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Arc {
+    pub ellipse: Ellipse,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Arc {
+    pub fn new(ellipse: Ellipse, start: f32, end: f32) -> Self {
+        Self {
+            ellipse,
+            start,
+            end,
+        }
+    }
+}
+
+/// Lazily yields the pixels of the outline inside the sector
+pub struct ArcIter<I = EllipseIter> {
+    outline: I,
+    center: Pixel,
+    sector: Sector,
+}
+
+impl<I> ArcIter<I> {
+    pub(crate) fn new(outline: I, center: Pixel, sector: Sector) -> Self {
+        Self {
+            outline,
+            center,
+            sector,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Pixel>> Iterator for ArcIter<I> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (center, sector) = (self.center, self.sector);
+        self.outline
+            .by_ref()
+            .find(|p| sector.contains(center, p.x(), p.y()))
+    }
+}
+
+impl Drawable for Arc {
+    type IntoIter = ArcIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        ArcIter::new(
+            self.ellipse.hollow().pixels(),
+            self.ellipse.center,
+            Sector::new(self.start, self.end),
+        )
+    }
+}
+
+/// A slice of an ellipse between two angles, see [`Arc`] for angles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pie {
+    pub ellipse: Ellipse,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Pie {
+    pub fn new(ellipse: Ellipse, start: f32, end: f32) -> Self {
+        Self {
+            ellipse,
+            start,
+            end,
+        }
+    }
+
+    pub fn hollow(self) -> HollowPie {
+        HollowPie(self)
+    }
+
+    pub fn filled(self) -> FilledPie {
+        FilledPie(self)
+    }
+}
+
+pub type PieIter<I = EllipseIter> =
+    std::iter::Chain<ArcIter<I>, std::iter::Chain<LineIter, LineIter>>;
+
+/// The arc and both radii to its ends, `ellipse` is the shape the arc is cut from
+pub(crate) fn pie_outline<I: Iterator<Item = Pixel>>(
+    arc: ArcIter<I>,
+    ellipse: Ellipse,
+    start: f32,
+    end: f32,
+) -> PieIter<I> {
+    let center = ellipse.center;
+
+    // a full pie has no radii
+    let (first, second) = if arc.sector.sweep >= TAU {
+        (center, center)
+    } else {
+        (ellipse.point_at(start), ellipse.point_at(end))
+    };
+
+    arc.chain(LineIter::new(center, first).chain(LineIter::new(center, second)))
+}
+
+/// Lazily splits the rows of the filled shape into the runs inside the sector
+pub struct PieSpans<S = EllipseSpans> {
+    spans: S,
+    center: Pixel,
+    sector: Sector,
+    remaining: Option<Span>,
+}
+
+impl<S> PieSpans<S> {
+    pub(crate) fn new(spans: S, center: Pixel, sector: Sector) -> Self {
+        Self {
+            spans,
+            center,
+            sector,
+            remaining: None,
+        }
+    }
+}
+
+impl<S: Iterator<Item = Span>> Iterator for PieSpans<S> {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let span = match self.remaining.take() {
+                Some(span) => span,
+                None => self.spans.next()?,
+            };
+
+            let inside = |x: &u32| self.sector.contains(self.center, *x, span.y);
+            let start = match (span.start..span.end).find(inside) {
+                Some(start) => start,
+                None => continue,
+            };
+            let end = (start..span.end).find(|x| !inside(x)).unwrap_or(span.end);
+
+            if end < span.end {
+                self.remaining = Some(Span::new(span.y, end, span.end));
+            }
+
+            return Some(Span::new(span.y, start, end));
+        }
+    }
+}
+
+pub type FilledPieIter<S = EllipseSpans> = SpanPixels<PieSpans<S>>;
+
+/// The arc and both radii to its ends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowPie(pub Pie);
+impl Drawable for HollowPie {
+    type IntoIter = PieIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        let Pie {
+            ellipse,
+            start,
+            end,
+        } = self.0;
+
+        pie_outline(Arc::new(ellipse, start, end).pixels(), ellipse, start, end)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledPie(pub Pie);
+impl Drawable for FilledPie {
+    type IntoIter = FilledPieIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledPie {
+    type Spans = PieSpans;
+
+    fn spans(&self) -> Self::Spans {
+        PieSpans::new(
+            ExtentSpans::new(self.0.ellipse, false),
+            self.0.ellipse.center,
+            Sector::new(self.0.start, self.0.end),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn quarter_pie_stays_in_its_quadrant() {
+        // Arrange, from the right to the bottom
+        let pie = Ellipse::new((20, 20).into(), 10, 10).pie(0.0, FRAC_PI_2);
+
+        // Act
+        let pixels: Vec<Pixel> = pie.filled().pixels().collect();
+
+        // Assert
+        assert!(pixels.iter().all(|p| p.x() >= 20 && p.y() >= 20));
+        assert!(pixels.contains(&(30, 20).into()));
+        assert!(pixels.contains(&(20, 30).into()));
+    }
+
+    #[test]
+    fn arc_with_negative_angles() {
+        // Arrange, the left half, from the bottom over the left to the top
+        let arc = Ellipse::new((20, 20).into(), 10, 5).arc(-FRAC_PI_2 - PI, -FRAC_PI_2);
+
+        // Act
+        let pixels: Vec<Pixel> = arc.pixels().collect();
+
+        // Assert
+        assert!(pixels.iter().all(|p| p.x() <= 20));
+        assert!(pixels.contains(&(10, 20).into()));
+    }
+}
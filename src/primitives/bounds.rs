@@ -0,0 +1,108 @@
+use crate::{draw::Drawable, pixel::Pixel};
+
+use super::{rectangle::Rectangle, span::RowExtents};
+
+/// Shapes, which can be picked, e.g. by a mouse click.
+/// Closed shapes are tested against the area they cover, when filled.
+pub trait HitTest {
+    /// The smallest rectangle around the shape, None if it covers no pixels
+    fn bounding_box(&self) -> Option<Rectangle>;
+
+    /// Whether the pixel is part of the shape
+    fn contains(&self, pixel: Pixel) -> bool;
+
+    /// Whether the pixel is part of the shape, when its bounding box is already known.
+    /// Shapes picked by their bounding box override this, to not compute it for every pixel.
+    fn contains_within(&self, pixel: Pixel, bounding_box: &Rectangle) -> bool {
+        let _ = bounding_box;
+        self.contains(pixel)
+    }
+
+    /// Whether both shapes share at least one pixel.
+    /// Only the pixels, in which their bounding boxes overlap, are tested.
+    fn intersects<H: HitTest + ?Sized>(&self, other: &H) -> bool {
+        let (a, b) = match (self.bounding_box(), other.bounding_box()) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+
+        match a.intersection(&b) {
+            Some(overlap) => overlap
+                .filled()
+                .pixels()
+                .any(|p| self.contains_within(p, &a) && other.contains_within(p, &b)),
+            None => false,
+        }
+    }
+}
+
+impl<H: HitTest + ?Sized> HitTest for &H {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        (*self).bounding_box()
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        (*self).contains(pixel)
+    }
+
+    fn contains_within(&self, pixel: Pixel, bounding_box: &Rectangle) -> bool {
+        (*self).contains_within(pixel, bounding_box)
+    }
+}
+
+/// The rectangle from `left`, `top` to the exclusive `right`, `bottom`,
+/// clipped to the positive coordinates
+pub(crate) fn bounding_box(left: i64, top: i64, right: i64, bottom: i64) -> Option<Rectangle> {
+    let (left, top) = (left.max(0), top.max(0));
+    if right <= left || bottom <= top {
+        return None;
+    }
+
+    Some(Rectangle::new(
+        Pixel((left as u32, top as u32)),
+        (right - left) as u32,
+        (bottom - top) as u32,
+    ))
+}
+
+pub(crate) fn extents_bounding_box(shape: &impl RowExtents) -> Option<Rectangle> {
+    let (first, last) = shape.rows();
+    let (left, right) = (first..=last)
+        .filter_map(|y| shape.extents(y))
+        .fold((i64::MAX, i64::MIN), |(left, right), (start, end)| {
+            (left.min(start), right.max(end))
+        });
+
+    bounding_box(left, first, right, last + 1)
+}
+
+pub(crate) fn extents_contain(shape: &impl RowExtents, pixel: Pixel) -> bool {
+    let x = pixel.x() as i64;
+    match shape.extents(pixel.y() as i64) {
+        Some((start, end)) => start <= x && x < end,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::{Circle, Line};
+
+    #[test]
+    fn intersections() {
+        // Arrange
+        let circle = Circle::new((10, 10).into(), 5);
+        let touching = Rectangle::new((14, 10).into(), 5, 5);
+        let in_the_corner = Rectangle::new((14, 14).into(), 5, 5);
+        let line = Line::new((0, 0).into(), (20, 10).into());
+
+        // Act, Assert
+        assert!(circle.intersects(&touching));
+        assert!(!circle.intersects(&in_the_corner));
+        assert!(line.contains((10, 5).into()));
+        assert!(!line.contains((10, 7).into()));
+        assert!(line.is_near((10, 7).into(), 2.0));
+        assert!(line.intersects(&circle));
+    }
+}
@@ -1,94 +1,402 @@
-use crate::{draw::Drawable, pixel::Pixel};
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
 
-use super::Line;
+use super::{
+    arc::{pie_outline, ArcIter, FilledPieIter, PieIter, PieSpans, Sector},
+    bounds::{bounding_box, HitTest},
+    ellipse::Ellipse,
+    rectangle::Rectangle,
+    span::{Span, SpanPixels},
+};
 
-pub(crate) struct Circle {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
     pub center: Pixel,
     pub radius: u32,
 }
 
+impl Circle {
+    pub fn new(center: Pixel, radius: u32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn hollow(self) -> HollowCircle {
+        HollowCircle(self)
+    }
+
+    pub fn filled(self) -> FilledCircle {
+        FilledCircle(self)
+    }
+
+    pub fn arc(self, start: f32, end: f32) -> CircleArc {
+        CircleArc::new(self, start, end)
+    }
+
+    pub fn pie(self, start: f32, end: f32) -> CirclePie {
+        CirclePie::new(self, start, end)
+    }
+}
+
+impl HitTest for Circle {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let (cx, cy) = (self.center.x() as i64, self.center.y() as i64);
+        let r = self.radius as i64;
+
+        bounding_box(cx - r, cy - r, cx + r, cy + r + 1)
+    }
+
+    /// Matches the pixels of the filled circle exactly, by finding the span on the row
+    fn contains(&self, pixel: Pixel) -> bool {
+        match self.filled().spans().find(|span| span.y == pixel.y()) {
+            Some(span) => span.start <= pixel.x() && pixel.x() < span.end,
+            None => false,
+        }
+    }
+}
+
+/// Midpoint circle stepping over one octant,
 /// https://gamedev.stackexchange.com/a/176060
-fn circle_pixels(circle: &Circle, is_filled: bool) -> Vec<Pixel> {
-    // pixel array
-    let mut pixels: Vec<Pixel> = Vec::new();
-
-    let mut d: i32 = (5 - circle.radius as i32 * 4) / 4;
-    let mut x = 0i32;
-    let mut y = circle.radius as i32;
-
-    loop {
-        if !is_filled {
-            pixels.push(circle.center + (x, y));
-            pixels.push(circle.center + (x, -y));
-            pixels.push(circle.center + (-x, y));
-            pixels.push(circle.center + (-x, -y));
-            pixels.push(circle.center + (y, x));
-            pixels.push(circle.center + (y, -x));
-            pixels.push(circle.center + (-y, x));
-            pixels.push(circle.center + (-y, -x));
-        } else {
-            pixels.extend(
-                Line {
-                    from: circle.center + (-x, y),
-                    to: circle.center + (x, y),
-                }
-                .pixels(),
-            );
-            pixels.extend(
-                Line {
-                    from: circle.center + (-x, -y),
-                    to: circle.center + (x, -y),
-                }
-                .pixels(),
-            );
-            pixels.extend(
-                Line {
-                    from: circle.center + (-y, x),
-                    to: circle.center + (y, x),
-                }
-                .pixels(),
-            );
-            pixels.extend(
-                Line {
-                    from: circle.center + (-y, -x),
-                    to: circle.center + (y, -x),
-                }
-                .pixels(),
-            );
+#[derive(Clone, Copy, Debug)]
+struct Midpoint {
+    d: i32,
+    x: i32,
+    y: i32,
+    done: bool,
+}
+
+impl Midpoint {
+    fn new(radius: u32) -> Self {
+        Self {
+            d: (5 - radius as i32 * 4) / 4,
+            x: 0,
+            y: radius as i32,
+            done: false,
         }
+    }
 
+    fn step(&mut self) {
         // Error correction
-        if d < 0 {
-            d += 2 * x as i32 + 1;
+        if self.d < 0 {
+            self.d += 2 * self.x + 1;
         } else {
-            d += 2 * (x as i32 - y as i32) + 1;
-            y -= 1;
+            self.d += 2 * (self.x - self.y) + 1;
+            self.y -= 1;
+        }
+
+        self.x += 1;
+
+        if self.x > self.y {
+            self.done = true;
         }
+    }
+}
+
+/// Lazily yields the outline of a circle, mirroring each step into all eight octants
+pub struct CircleIter {
+    center: Pixel,
+    midpoint: Midpoint,
+    octant: u8,
+}
 
-        x += 1;
+impl Iterator for CircleIter {
+    type Item = Pixel;
 
-        if x > y {
-            break;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.midpoint.done {
+                return None;
+            }
+
+            let (x, y) = (self.midpoint.x, self.midpoint.y);
+            let offset = match self.octant {
+                0 => (x, y),
+                1 => (x, -y),
+                2 => (-x, y),
+                3 => (-x, -y),
+                4 => (y, x),
+                5 => (y, -x),
+                6 => (-y, x),
+                7 => (-y, -x),
+                _ => {
+                    self.octant = 0;
+                    self.midpoint.step();
+                    continue;
+                }
+            };
+
+            self.octant += 1;
+            return Some(self.center + offset);
         }
     }
+}
 
-    pixels
+/// Lazily yields every row of a filled circle exactly once.
+///
+/// Each midpoint step covers the rows at `±y` with a half width of `x`
+/// and the rows at `±x` with a half width of `y`.
+/// The first pass emits the `±y` rows once `y` is about to change, so only their widest run is kept,
+/// the second pass emits the `±x` rows, which are distinct for each step.
+pub struct FilledCircleSpans {
+    center: (i64, i64),
+    radius: u32,
+    midpoint: Midpoint,
+    second_pass: bool,
+    pending: Option<Span>,
+}
+
+impl FilledCircleSpans {
+    fn new(circle: &Circle) -> Self {
+        Self {
+            center: (circle.center.x() as i64, circle.center.y() as i64),
+            radius: circle.radius,
+            midpoint: Midpoint::new(circle.radius),
+            second_pass: false,
+            pending: None,
+        }
+    }
+
+    /// The spans at `±offset` rows from the center, the upper one is kept pending
+    fn rows(&mut self, offset: i32, half_width: i32) -> Option<Span> {
+        let (cx, cy) = self.center;
+        let (offset, half_width) = (offset as i64, half_width as i64);
+        let span = |y: i64| Span::clipped(y, cx - half_width, cx + half_width);
+
+        if offset != 0 {
+            self.pending = span(cy - offset);
+        }
+
+        span(cy + offset)
+    }
+}
+
+impl Iterator for FilledCircleSpans {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(span) = self.pending.take() {
+                return Some(span);
+            }
+
+            if self.midpoint.done {
+                if self.second_pass {
+                    return None;
+                }
+
+                self.second_pass = true;
+                self.midpoint = Midpoint::new(self.radius);
+            }
+
+            let (x, y) = (self.midpoint.x, self.midpoint.y);
+            self.midpoint.step();
+
+            let span = if !self.second_pass {
+                if !self.midpoint.done && self.midpoint.y == y {
+                    continue;
+                }
+                self.rows(y, x)
+            } else {
+                // the row at x == y was already emitted by the first pass
+                if x == y {
+                    continue;
+                }
+                self.rows(x, y)
+            };
+
+            if span.is_some() {
+                return span;
+            }
+        }
+    }
 }
 
-pub(crate) struct HollowCircle(pub Circle);
+pub type FilledCircleIter = SpanPixels<FilledCircleSpans>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowCircle(pub Circle);
 impl Drawable for HollowCircle {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+    type IntoIter = CircleIter;
 
     fn pixels(&self) -> Self::IntoIter {
-        circle_pixels(&self.0, false).into_iter()
+        CircleIter {
+            center: self.0.center,
+            midpoint: Midpoint::new(self.0.radius),
+            octant: 0,
+        }
     }
 }
 
-pub(crate) struct FilledCircle(pub Circle);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledCircle(pub Circle);
 impl Drawable for FilledCircle {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+    type IntoIter = FilledCircleIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledCircle {
+    type Spans = FilledCircleSpans;
+
+    fn spans(&self) -> Self::Spans {
+        FilledCircleSpans::new(&self.0)
+    }
+}
+
+/// A part of the outline of a circle, see [`Arc`](super::Arc) for angles.
+/// Its pixels are those of the [`HollowCircle`] between the angles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CircleArc {
+    pub circle: Circle,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl CircleArc {
+    pub fn new(circle: Circle, start: f32, end: f32) -> Self {
+        Self { circle, start, end }
+    }
+}
+
+impl Drawable for CircleArc {
+    type IntoIter = ArcIter<CircleIter>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        ArcIter::new(
+            self.circle.hollow().pixels(),
+            self.circle.center,
+            Sector::new(self.start, self.end),
+        )
+    }
+}
+
+/// A slice of a circle between two angles, see [`Arc`](super::Arc) for angles.
+/// Its pixels are those of the circle between the angles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CirclePie {
+    pub circle: Circle,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl CirclePie {
+    pub fn new(circle: Circle, start: f32, end: f32) -> Self {
+        Self { circle, start, end }
+    }
+
+    pub fn hollow(self) -> HollowCirclePie {
+        HollowCirclePie(self)
+    }
+
+    pub fn filled(self) -> FilledCirclePie {
+        FilledCirclePie(self)
+    }
+}
+
+/// The arc and both radii to its ends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowCirclePie(pub CirclePie);
+impl Drawable for HollowCirclePie {
+    type IntoIter = PieIter<CircleIter>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        let CirclePie { circle, start, end } = self.0;
+        let arc = CircleArc::new(circle, start, end).pixels();
+
+        pie_outline(arc, Ellipse::from(circle), start, end)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledCirclePie(pub CirclePie);
+impl Drawable for FilledCirclePie {
+    type IntoIter = FilledPieIter<FilledCircleSpans>;
 
     fn pixels(&self) -> Self::IntoIter {
-        circle_pixels(&self.0, true).into_iter()
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledCirclePie {
+    type Spans = PieSpans<FilledCircleSpans>;
+
+    fn spans(&self) -> Self::Spans {
+        let CirclePie { circle, start, end } = self.0;
+
+        PieSpans::new(
+            circle.filled().spans(),
+            circle.center,
+            Sector::new(start, end),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{
+        collections::HashSet,
+        f32::consts::{FRAC_PI_2, TAU},
+    };
+
+    #[test]
+    fn filled_spans_do_not_overlap() {
+        for radius in 0..20 {
+            // Arrange
+            let circle = Circle::new((30, 30).into(), radius).filled();
+
+            // Act
+            let spans: Vec<Span> = circle.spans().collect();
+            let rows: HashSet<u32> = spans.iter().map(|s| s.y).collect();
+
+            // Assert
+            assert_eq!(spans.len(), rows.len());
+            if radius > 0 {
+                assert!(spans.contains(&Span::new(30, 30 - radius, 30 + radius)));
+            }
+        }
+    }
+
+    #[test]
+    fn contains_matches_fill() {
+        for radius in 1..20 {
+            // Arrange
+            let circle = Circle::new((30, 30).into(), radius);
+
+            // Act
+            let mut filled: Vec<Pixel> = circle.filled().pixels().collect();
+            let bb = circle.bounding_box().unwrap();
+            let mut contained: Vec<Pixel> = bb
+                .filled()
+                .pixels()
+                .filter(|p| circle.contains(*p))
+                .collect();
+            filled.sort_by_key(|p| (p.y(), p.x()));
+            contained.sort_by_key(|p| (p.y(), p.x()));
+
+            // Assert
+            assert_eq!(filled, contained);
+        }
+    }
+
+    #[test]
+    fn arcs_follow_the_outline() {
+        // Arrange
+        let circle = Circle::new((30, 30).into(), 12);
+
+        // Act
+        let outline: Vec<Pixel> = circle.hollow().pixels().collect();
+        let full: Vec<Pixel> = circle.arc(0.0, TAU).pixels().collect();
+        let quarter: Vec<Pixel> = circle.arc(0.0, FRAC_PI_2).pixels().collect();
+        let full_pie: Vec<Span> = circle.pie(0.0, TAU).filled().spans().collect();
+
+        // Assert
+        assert_eq!(full, outline);
+        assert!(quarter.iter().all(|p| outline.contains(p)));
+        assert!(quarter.iter().all(|p| p.x() >= 30 && p.y() >= 30));
+        assert_eq!(full_pie, circle.filled().spans().collect::<Vec<_>>());
     }
 }
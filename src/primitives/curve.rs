@@ -0,0 +1,503 @@
+use bresenham::Bresenham;
+
+use crate::{draw::Drawable, pixel::Pixel};
+
+use super::stroke::{Stroke, Stroked};
+
+type Point = (f32, f32);
+
+/// The largest distance in pixels between a curve and the lines it is drawn with
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// Limits the subdivision of a single segment to 2^12 lines
+const MAX_DEPTH: u32 = 12;
+
+/// Samples per segment when measuring a curve.
+/// Flattening is not enough, as a straight curve may still change its speed.
+const ARC_LENGTH_SAMPLES: usize = 128;
+
+/// A smooth curve parameterised from `t = 0.0` at its start to `t = 1.0` at its end.
+///
+/// Points are in the same coordinates as a [`Path`](super::Path), based on pixel corners.
+pub trait Curve {
+    /// The position at `t`, which is clamped to 0.0..=1.0
+    fn point(&self, t: f32) -> Point;
+
+    /// The derivative at `t`, pointing in the direction of travel
+    fn tangent(&self, t: f32) -> Point;
+
+    /// How many pieces the curve is made of, subdivision starts from their joints
+    fn segments(&self) -> usize {
+        1
+    }
+
+    /// Approximates the curve with lines, adding points where it bends more than the tolerance
+    fn flatten(&self, tolerance: f32) -> Vec<(f32, Point)>
+    where
+        Self: Sized,
+    {
+        let segments = self.segments().max(1);
+        let mut points = vec![(0.0, self.point(0.0))];
+        for i in 0..segments {
+            let from = i as f32 / segments as f32;
+            let to = (i + 1) as f32 / segments as f32;
+            subdivide(self, from, to, tolerance.max(0.01), 0, &mut points);
+        }
+
+        points
+    }
+
+    /// Measures the curve, so it can be sampled by distance
+    fn arc_length(&self) -> ArcLength
+    where
+        Self: Sized,
+    {
+        let count = self.segments().max(1) * ARC_LENGTH_SAMPLES;
+        let points: Vec<(f32, Point)> = (0..=count)
+            .map(|i| {
+                let t = i as f32 / count as f32;
+                (t, self.point(t))
+            })
+            .collect();
+
+        ArcLength::new(&points)
+    }
+
+    /// The position after travelling `distance` pixels along the curve
+    fn point_at_distance(&self, arc_length: &ArcLength, distance: f32) -> Point {
+        self.point(arc_length.t_at(distance))
+    }
+
+    /// Strokes the curve with a width
+    fn stroked(&self, stroke: Stroke) -> Stroked
+    where
+        Self: Sized,
+    {
+        let points = self
+            .flatten(DEFAULT_TOLERANCE)
+            .into_iter()
+            .map(|(_, (x, y))| (x as f64, y as f64))
+            .collect();
+
+        Stroked::new(points, false, stroke)
+    }
+}
+
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Distance of the point to the line segment between `from` and `to`
+fn distance_to_chord(point: Point, from: Point, to: Point) -> f32 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+
+    let closest = lerp(from, to, t);
+    (point.0 - closest.0).hypot(point.1 - closest.1)
+}
+
+/// Adds the points after `from` up to and including `to`
+fn subdivide<C: Curve>(
+    curve: &C,
+    from: f32,
+    to: f32,
+    tolerance: f32,
+    depth: u32,
+    points: &mut Vec<(f32, Point)>,
+) {
+    let (start, end) = (curve.point(from), curve.point(to));
+
+    // checking several points catches s-shapes, whose middle lies on the chord
+    let flat = [0.25, 0.5, 0.75].iter().all(|&f| {
+        let point = curve.point(from + (to - from) * f);
+        distance_to_chord(point, start, end) <= tolerance
+    });
+
+    if flat || depth >= MAX_DEPTH {
+        points.push((to, end));
+    } else {
+        let middle = (from + to) / 2.0;
+        subdivide(curve, from, middle, tolerance, depth + 1, points);
+        subdivide(curve, middle, to, tolerance, depth + 1, points);
+    }
+}
+
+/// A lookup table from distance along a curve to its parameter `t`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArcLength {
+    /// `t` and the distance from the start at that point
+    samples: Vec<(f32, f32)>,
+}
+
+impl ArcLength {
+    fn new(points: &[(f32, Point)]) -> Self {
+        let mut distance = 0.0;
+        let mut samples = Vec::with_capacity(points.len());
+        for (i, &(t, point)) in points.iter().enumerate() {
+            if i > 0 {
+                let previous = points[i - 1].1;
+                distance += (point.0 - previous.0).hypot(point.1 - previous.1);
+            }
+            samples.push((t, distance));
+        }
+
+        Self { samples }
+    }
+
+    /// The total length in pixels
+    pub fn length(&self) -> f32 {
+        self.samples.last().map_or(0.0, |s| s.1)
+    }
+
+    /// The parameter `t` after travelling `distance` pixels, clamped to the ends of the curve
+    pub fn t_at(&self, distance: f32) -> f32 {
+        let index = self.samples.partition_point(|s| s.1 < distance);
+        if index == 0 {
+            return 0.0;
+        }
+        if index >= self.samples.len() {
+            return 1.0;
+        }
+
+        let (t0, d0) = self.samples[index - 1];
+        let (t1, d1) = self.samples[index];
+        if d1 == d0 {
+            t0
+        } else {
+            t0 + (t1 - t0) * (distance - d0) / (d1 - d0)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QuadraticBezier {
+    pub from: Point,
+    pub control: Point,
+    pub to: Point,
+}
+
+impl QuadraticBezier {
+    pub fn new(from: Point, control: Point, to: Point) -> Self {
+        Self { from, control, to }
+    }
+}
+
+impl Curve for QuadraticBezier {
+    fn point(&self, t: f32) -> Point {
+        let t = t.clamp(0.0, 1.0);
+        lerp(
+            lerp(self.from, self.control, t),
+            lerp(self.control, self.to, t),
+            t,
+        )
+    }
+
+    fn tangent(&self, t: f32) -> Point {
+        let t = t.clamp(0.0, 1.0);
+        let (a, b) = (self.from, self.control);
+        let c = self.to;
+
+        (
+            2.0 * (1.0 - t) * (b.0 - a.0) + 2.0 * t * (c.0 - b.0),
+            2.0 * (1.0 - t) * (b.1 - a.1) + 2.0 * t * (c.1 - b.1),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    pub from: Point,
+    pub control_from: Point,
+    pub control_to: Point,
+    pub to: Point,
+}
+
+impl CubicBezier {
+    pub fn new(from: Point, control_from: Point, control_to: Point, to: Point) -> Self {
+        Self {
+            from,
+            control_from,
+            control_to,
+            to,
+        }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn point(&self, t: f32) -> Point {
+        let t = t.clamp(0.0, 1.0);
+        let a = lerp(self.from, self.control_from, t);
+        let b = lerp(self.control_from, self.control_to, t);
+        let c = lerp(self.control_to, self.to, t);
+
+        lerp(lerp(a, b, t), lerp(b, c, t), t)
+    }
+
+    fn tangent(&self, t: f32) -> Point {
+        let t = t.clamp(0.0, 1.0);
+        let (p0, p1, p2, p3) = (self.from, self.control_from, self.control_to, self.to);
+        let derivative = |a: f32, b: f32, c: f32, d: f32| {
+            3.0 * (1.0 - t) * (1.0 - t) * (b - a)
+                + 6.0 * (1.0 - t) * t * (c - b)
+                + 3.0 * t * t * (d - c)
+        };
+
+        (
+            derivative(p0.0, p1.0, p2.0, p3.0),
+            derivative(p0.1, p1.1, p2.1, p3.1),
+        )
+    }
+}
+
+/// A spline passing through all of its points, with a uniform parameter per segment.
+/// The first and last point are repeated to give the end segments their tangents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatmullRom {
+    pub points: Vec<Point>,
+}
+
+impl CatmullRom {
+    pub fn new(points: Vec<Point>) -> Self {
+        Self { points }
+    }
+
+    /// The four points around the segment at `t` and the position inside of it
+    fn segment(&self, t: f32) -> ([Point; 4], f32) {
+        let last = self.points.len() as isize - 1;
+        let segments = self.segments() as f32;
+        let scaled = t.clamp(0.0, 1.0) * segments;
+        let index = (scaled.floor() as isize).min(last - 1).max(0);
+
+        let point = |i: isize| self.points[i.clamp(0, last.max(0)) as usize];
+        (
+            [
+                point(index - 1),
+                point(index),
+                point(index + 1),
+                point(index + 2),
+            ],
+            scaled - index as f32,
+        )
+    }
+}
+
+impl Curve for CatmullRom {
+    fn point(&self, t: f32) -> Point {
+        if self.points.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let ([p0, p1, p2, p3], u) = self.segment(t);
+        let axis = |a: f32, b: f32, c: f32, d: f32| {
+            0.5 * (2.0 * b
+                + (c - a) * u
+                + (2.0 * a - 5.0 * b + 4.0 * c - d) * u * u
+                + (3.0 * b - a - 3.0 * c + d) * u * u * u)
+        };
+
+        (axis(p0.0, p1.0, p2.0, p3.0), axis(p0.1, p1.1, p2.1, p3.1))
+    }
+
+    fn tangent(&self, t: f32) -> Point {
+        if self.points.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let ([p0, p1, p2, p3], u) = self.segment(t);
+        let segments = self.segments() as f32;
+        let axis = |a: f32, b: f32, c: f32, d: f32| {
+            0.5 * segments
+                * ((c - a)
+                    + 2.0 * (2.0 * a - 5.0 * b + 4.0 * c - d) * u
+                    + 3.0 * (3.0 * b - a - 3.0 * c + d) * u * u)
+        };
+
+        (axis(p0.0, p1.0, p2.0, p3.0), axis(p0.1, p1.1, p2.1, p3.1))
+    }
+
+    fn segments(&self) -> usize {
+        self.points.len().saturating_sub(1).max(1)
+    }
+}
+
+/// Lazily walks the lines of a flattened curve, skipping the pixels at negative coordinates
+pub struct CurveIter {
+    points: Vec<(i64, i64)>,
+    index: usize,
+    line: Option<Bresenham>,
+}
+
+impl CurveIter {
+    fn new<C: Curve>(curve: &C) -> Self {
+        let mut points: Vec<(i64, i64)> = curve
+            .flatten(DEFAULT_TOLERANCE)
+            .into_iter()
+            // each point lies in the pixel, whose corner it rounds down to
+            .map(|(_, (x, y))| (x.floor() as i64, y.floor() as i64))
+            .collect();
+        points.dedup();
+
+        Self {
+            points,
+            index: 0,
+            line: None,
+        }
+    }
+}
+
+fn to_pixel((x, y): (i64, i64)) -> Option<Pixel> {
+    if x < 0 || y < 0 {
+        None
+    } else {
+        Some(Pixel((x as u32, y as u32)))
+    }
+}
+
+impl Iterator for CurveIter {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // lines are stepped in signed coordinates, so they can leave and reenter the buffer
+            let pixel = self
+                .line
+                .as_mut()
+                .and_then(|line| line.find_map(|(x, y)| to_pixel((x as i64, y as i64))));
+            if pixel.is_some() {
+                return pixel;
+            }
+
+            // lines exclude their end, so the last point is yielded on its own
+            if self.index + 1 >= self.points.len() {
+                let last = self.points.get(self.index).copied();
+                self.index = self.points.len();
+                return last.and_then(to_pixel);
+            }
+
+            let (from, to) = (self.points[self.index], self.points[self.index + 1]);
+            self.index += 1;
+            self.line = Some(Bresenham::new(
+                (from.0 as isize, from.1 as isize),
+                (to.0 as isize, to.1 as isize),
+            ));
+        }
+    }
+}
+
+macro_rules! curve_drawables {
+    ($($curve:ty),*) => {
+        $(
+            impl Drawable for $curve {
+                type IntoIter = CurveIter;
+
+                fn pixels(&self) -> Self::IntoIter {
+                    CurveIter::new(self)
+                }
+            }
+        )*
+    };
+}
+
+curve_drawables!(QuadraticBezier, CubicBezier, CatmullRom);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{draw::SpanDrawable, primitives::Path};
+
+    #[test]
+    fn curves_pass_through_their_ends() {
+        // Arrange
+        let quadratic = QuadraticBezier::new((0.0, 0.0), (10.0, 20.0), (20.0, 0.0));
+        let cubic = CubicBezier::new((0.0, 0.0), (0.0, 10.0), (20.0, -10.0), (20.0, 0.0));
+        let spline = CatmullRom::new(vec![(0.0, 0.0), (5.0, 5.0), (10.0, 0.0), (15.0, 5.0)]);
+
+        // Act, Assert
+        assert_eq!(quadratic.point(0.0), (0.0, 0.0));
+        assert_eq!(quadratic.point(0.5), (10.0, 10.0));
+        assert_eq!(quadratic.tangent(0.5), (20.0, 0.0));
+        assert_eq!(cubic.point(1.0), (20.0, 0.0));
+        assert_eq!(spline.point(0.0), (0.0, 0.0));
+        assert_eq!(spline.point(1.0), (15.0, 5.0));
+    }
+
+    #[test]
+    fn flatten_keeps_within_tolerance() {
+        // Arrange
+        let cubic = CubicBezier::new((0.0, 0.0), (0.0, 40.0), (40.0, -40.0), (40.0, 0.0));
+
+        // Act
+        let points = cubic.flatten(0.5);
+
+        // Assert
+        assert!(points.len() > 4);
+        for window in points.windows(2) {
+            let (t0, from) = window[0];
+            let (t1, to) = window[1];
+            let middle = cubic.point((t0 + t1) / 2.0);
+            assert!(distance_to_chord(middle, from, to) <= 0.5);
+        }
+    }
+
+    #[test]
+    fn sample_by_distance() {
+        // Arrange, a straight line, which is 30 pixels long
+        let line = QuadraticBezier::new((0.0, 0.0), (5.0, 0.0), (30.0, 0.0));
+        let arc_length = line.arc_length();
+
+        // Act
+        let middle = line.point_at_distance(&arc_length, 15.0);
+
+        // Assert
+        assert!((arc_length.length() - 30.0).abs() < 0.01);
+        assert!((middle.0 - 15.0).abs() < 0.1);
+        assert_eq!(line.point_at_distance(&arc_length, 100.0), (30.0, 0.0));
+    }
+
+    #[test]
+    fn strokes_match_paths() {
+        // Arrange
+        let (from, control_from, control_to, to) =
+            ((5.0, 5.0), (5.0, 25.0), (30.0, 0.0), (30.0, 20.0));
+        let stroke = Stroke::new(3.0);
+
+        // Act
+        let curve = CubicBezier::new(from, control_from, control_to, to).stroked(stroke);
+        let path = Path::new()
+            .move_to(from)
+            .cubic_to(control_from, control_to, to)
+            .stroked(stroke);
+
+        // Assert
+        assert_eq!(
+            curve.spans().collect::<Vec<_>>(),
+            path.spans().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn curves_leaving_the_buffer() {
+        // Arrange, from inside over the top left corner and back
+        let curve = QuadraticBezier::new((10.0, 2.0), (-10.0, -10.0), (2.0, 10.0));
+        let shifted = QuadraticBezier::new((30.0, 22.0), (10.0, 10.0), (22.0, 30.0));
+
+        // Act
+        let pixels: Vec<Pixel> = curve.pixels().collect();
+        let visible: Vec<Pixel> = shifted
+            .pixels()
+            .filter(|p| p.x() >= 20 && p.y() >= 20)
+            .map(|p| p - Pixel((20, 20)))
+            .collect();
+
+        // Assert
+        assert_eq!(pixels, visible);
+        // the lines crossing the edges are kept up to the edge
+        assert!(pixels.iter().any(|p| p.y() == 0));
+        assert!(pixels.iter().any(|p| p.x() == 0));
+    }
+}
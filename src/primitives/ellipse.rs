@@ -0,0 +1,157 @@
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    arc::{Arc, Pie},
+    bounds::{extents_bounding_box, extents_contain, HitTest},
+    circle::Circle,
+    rectangle::Rectangle,
+    span::{ExtentSpans, RowExtents, SpanPixels},
+};
+
+/// An axis aligned ellipse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ellipse {
+    pub center: Pixel,
+    pub radius_x: u32,
+    pub radius_y: u32,
+}
+
+impl Ellipse {
+    pub fn new(center: Pixel, radius_x: u32, radius_y: u32) -> Self {
+        Self {
+            center,
+            radius_x,
+            radius_y,
+        }
+    }
+
+    /// The point on the outline in the direction of the given angle, see [`Arc`] for angles
+    pub fn point_at(&self, angle: f32) -> Pixel {
+        let (rx, ry) = (self.radius_x as f32, self.radius_y as f32);
+        let (sin, cos) = angle.sin_cos();
+        let length = (ry * cos).hypot(rx * sin);
+        let distance = if length == 0.0 { 0.0 } else { rx * ry / length };
+
+        self.center
+            + (
+                (distance * cos).round() as i32,
+                (distance * sin).round() as i32,
+            )
+    }
+
+    pub fn hollow(self) -> HollowEllipse {
+        HollowEllipse(self)
+    }
+
+    pub fn filled(self) -> FilledEllipse {
+        FilledEllipse(self)
+    }
+
+    pub fn arc(self, start: f32, end: f32) -> Arc {
+        Arc::new(self, start, end)
+    }
+
+    pub fn pie(self, start: f32, end: f32) -> Pie {
+        Pie::new(self, start, end)
+    }
+}
+
+impl From<Circle> for Ellipse {
+    fn from(circle: Circle) -> Self {
+        Ellipse::new(circle.center, circle.radius, circle.radius)
+    }
+}
+
+/// Covers every pixel, whose center lies inside the ellipse grown by half a pixel
+impl RowExtents for Ellipse {
+    fn rows(&self) -> (i64, i64) {
+        let cy = self.center.y() as i64;
+        let ry = self.radius_y as i64;
+
+        (cy - ry, cy + ry)
+    }
+
+    fn extents(&self, y: i64) -> Option<(i64, i64)> {
+        let dy = (y - self.center.y() as i64).abs();
+        if dy > self.radius_y as i64 {
+            return None;
+        }
+
+        // half a pixel is added to the radii, so the extreme rows are not a single pixel
+        let t = dy as f64 / (self.radius_y as f64 + 0.5);
+        let half_width = ((self.radius_x as f64 + 0.5) * (1.0 - t * t).sqrt()).floor() as i64;
+
+        let cx = self.center.x() as i64;
+        Some((cx - half_width, cx + half_width + 1))
+    }
+}
+
+impl HitTest for Ellipse {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        extents_bounding_box(self)
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        extents_contain(self, pixel)
+    }
+}
+
+pub type EllipseSpans = ExtentSpans<Ellipse>;
+pub type EllipseIter = SpanPixels<EllipseSpans>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowEllipse(pub Ellipse);
+impl Drawable for HollowEllipse {
+    type IntoIter = EllipseIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(ExtentSpans::new(self.0, true))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledEllipse(pub Ellipse);
+impl Drawable for FilledEllipse {
+    type IntoIter = EllipseIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledEllipse {
+    type Spans = EllipseSpans;
+
+    fn spans(&self) -> Self::Spans {
+        ExtentSpans::new(self.0, false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn outline_is_closed_and_inside_fill() {
+        // Arrange
+        let ellipse = Ellipse::new((40, 30).into(), 25, 9);
+
+        // Act
+        let outline: HashSet<Pixel> = ellipse.hollow().pixels().collect();
+        let filled: HashSet<Pixel> = ellipse.filled().pixels().collect();
+
+        // Assert
+        assert!(outline.is_subset(&filled));
+        for x in 15..=65 {
+            let column = filled.iter().filter(|p| p.x() == x);
+            let top = column.clone().map(|p| p.y()).min().unwrap();
+            let bottom = column.map(|p| p.y()).max().unwrap();
+            assert!(outline.contains(&(x, top).into()));
+            assert!(outline.contains(&(x, bottom).into()));
+        }
+    }
+}
@@ -1,22 +1,245 @@
-use crate::{draw::Drawable, pixel::Pixel};
+use crate::{
+    draw::{CoverageDrawable, Drawable},
+    pixel::Pixel,
+};
 use bresenham::Bresenham;
 
-pub(crate) struct Line {
+use super::{
+    bounds::{bounding_box, HitTest},
+    rectangle::Rectangle,
+    stroke::{Stroke, Stroked},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line {
     pub from: Pixel,
     pub to: Pixel,
 }
 
+impl Line {
+    pub fn new(from: Pixel, to: Pixel) -> Self {
+        Self { from, to }
+    }
+
+    /// Only draws the pixels, whose bit in the pattern is set.
+    /// The pattern is rotated by one bit per pixel, starting with the highest bit.
+    pub fn dashed(self, pattern: u32) -> DashedLine {
+        DashedLine {
+            line: self,
+            pattern,
+        }
+    }
+
+    pub fn stroked(self, stroke: Stroke) -> Stroked {
+        Stroked::through_pixels(&[self.from, self.to], false, stroke)
+    }
+
+    pub fn anti_aliased(self) -> AntiAliasedLine {
+        AntiAliasedLine(self)
+    }
+
+    /// The distance between the centers of the pixel and of the nearest pixel on the line
+    pub fn distance_to(&self, pixel: Pixel) -> f32 {
+        let (x0, y0) = (self.from.x() as f32, self.from.y() as f32);
+        let (dx, dy) = (self.to.x() as f32 - x0, self.to.y() as f32 - y0);
+        let (px, py) = (pixel.x() as f32 - x0, pixel.y() as f32 - y0);
+
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            ((px * dx + py * dy) / length_squared).clamp(0.0, 1.0)
+        };
+
+        (px - t * dx).hypot(py - t * dy)
+    }
+
+    /// Whether the pixel lies at most `tolerance` pixels away from the line,
+    /// so thin lines can be picked without hitting them exactly
+    pub fn is_near(&self, pixel: Pixel, tolerance: f32) -> bool {
+        self.distance_to(pixel) <= tolerance
+    }
+}
+
+/// Includes both end pixels, pixels within half a pixel of the line are contained
+impl HitTest for Line {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let (x0, y0) = (self.from.x() as i64, self.from.y() as i64);
+        let (x1, y1) = (self.to.x() as i64, self.to.y() as i64);
+
+        bounding_box(x0.min(x1), y0.min(y1), x0.max(x1) + 1, y0.max(y1) + 1)
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        self.is_near(pixel, 0.5)
+    }
+}
+
+/// Lazily steps along a line, the end pixel is exclusive
+pub struct LineIter {
+    inner: Bresenham,
+}
+
+impl LineIter {
+    pub(crate) fn new(from: Pixel, to: Pixel) -> Self {
+        Self {
+            inner: Bresenham::new(
+                (from.x() as isize, from.y() as isize),
+                (to.x() as isize, to.y() as isize),
+            ),
+        }
+    }
+}
+
+impl Iterator for LineIter {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(x, y)| Pixel((x as u32, y as u32)))
+    }
+}
+
 impl Drawable for Line {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+    type IntoIter = LineIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        LineIter::new(self.from, self.to)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DashedLine {
+    pub line: Line,
+    pub pattern: u32,
+}
+
+/// Lazily steps along a line, skipping the pixels whose pattern bit is not set
+pub struct DashedLineIter {
+    inner: LineIter,
+    pattern: u32,
+}
+
+impl Iterator for DashedLineIter {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let pixel = self.inner.next()?;
+            self.pattern = self.pattern.rotate_left(1);
+
+            if self.pattern & 1 != 0 {
+                return Some(pixel);
+            }
+        }
+    }
+}
+
+impl Drawable for DashedLine {
+    type IntoIter = DashedLineIter;
 
     fn pixels(&self) -> Self::IntoIter {
-        Bresenham::new(
-            (self.from.x() as isize, self.from.y() as isize),
-            (self.to.x() as isize, self.to.y() as isize),
-        )
-        .map(|(x, y)| Pixel((x as u32, y as u32)))
-        .collect::<Vec<Pixel>>()
-        .into_iter()
+        DashedLineIter {
+            inner: self.line.pixels(),
+            pattern: self.pattern,
+        }
+    }
+}
+
+/// A line drawn with Xiaolin Wu's algorithm, the end pixel is exclusive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AntiAliasedLine(pub Line);
+
+/// Lazily steps along the major axis of a line,
+/// covering the two pixels closest to the line by their distance to it
+pub struct WuIter {
+    from: (f32, f32),
+    gradient: f32,
+    steep: bool,
+    step: i64,
+    end: i64,
+    position: i64,
+    pending: Option<(Pixel, f32)>,
+}
+
+impl WuIter {
+    fn new(line: &Line) -> Self {
+        let (x0, y0) = (line.from.x() as f32, line.from.y() as f32);
+        let (x1, y1) = (line.to.x() as f32, line.to.y() as f32);
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+
+        // walk along x, swapping the axes for steep lines
+        let (from, to) = if steep {
+            ((y0, x0), (y1, x1))
+        } else {
+            ((x0, y0), (x1, y1))
+        };
+        let gradient = if to.0 == from.0 {
+            0.0
+        } else {
+            (to.1 - from.1) / (to.0 - from.0)
+        };
+
+        Self {
+            from,
+            gradient,
+            steep,
+            step: if to.0 < from.0 { -1 } else { 1 },
+            end: to.0 as i64,
+            position: from.0 as i64,
+            pending: None,
+        }
+    }
+
+    fn pixel(&self, major: i64, minor: i64) -> Option<Pixel> {
+        let (x, y) = if self.steep {
+            (minor, major)
+        } else {
+            (major, minor)
+        };
+
+        if x < 0 || y < 0 {
+            None
+        } else {
+            Some(Pixel((x as u32, y as u32)))
+        }
+    }
+}
+
+impl Iterator for WuIter {
+    type Item = (Pixel, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pending) = self.pending.take() {
+                return Some(pending);
+            }
+
+            if self.position == self.end {
+                return None;
+            }
+
+            let major = self.position;
+            self.position += self.step;
+
+            let minor = self.from.1 + self.gradient * (major as f32 - self.from.0);
+            let fraction = minor - minor.floor();
+            let minor = minor.floor() as i64;
+
+            if fraction > 0.0 {
+                self.pending = self.pixel(major, minor + 1).map(|p| (p, fraction));
+            }
+            if let Some(pixel) = self.pixel(major, minor) {
+                return Some((pixel, 1.0 - fraction));
+            }
+        }
+    }
+}
+
+impl CoverageDrawable for AntiAliasedLine {
+    type Coverage = WuIter;
+
+    fn coverage(&self) -> Self::Coverage {
+        WuIter::new(&self.0)
     }
 }
 
@@ -43,7 +266,7 @@ mod test {
 
         expected
             .into_iter()
-            .zip(line.pixels().into_iter())
+            .zip(line.pixels())
             .for_each(|(e, a)| assert_eq!(e, a));
 
         let line = Line {
@@ -62,7 +285,39 @@ mod test {
 
         expected
             .into_iter()
-            .zip(line.pixels().into_iter())
+            .zip(line.pixels())
             .for_each(|(e, a)| assert_eq!(e, a))
     }
+
+    #[test]
+    fn dashed_line() {
+        // Arrange
+        let line = Line::new((0, 0).into(), (8, 0).into()).dashed(0xcccc_cccc);
+
+        // Act
+        let pixels: Vec<u32> = line.pixels().map(|p| p.x()).collect();
+
+        // Assert
+        assert_eq!(pixels, vec![0, 1, 4, 5]);
+    }
+
+    #[test]
+    fn anti_aliased_coverage_sums_to_one() {
+        // Arrange
+        let line = Line::new((0, 0).into(), (10, 3).into()).anti_aliased();
+
+        // Act
+        let coverage: Vec<(Pixel, f32)> = line.coverage().collect();
+
+        // Assert
+        for x in 0..10 {
+            let sum: f32 = coverage
+                .iter()
+                .filter(|(p, _)| p.x() == x)
+                .map(|(_, c)| c)
+                .sum();
+            assert!((sum - 1.0).abs() < 1e-5);
+        }
+        assert_eq!(coverage[0], ((0, 0).into(), 1.0));
+    }
 }
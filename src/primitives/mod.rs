@@ -1,8 +1,52 @@
+mod arc;
+pub use arc::{Arc, ArcIter, FilledPie, FilledPieIter, HollowPie, Pie, PieIter, PieSpans};
+
+mod bounds;
+pub(crate) use bounds::bounding_box;
+pub use bounds::HitTest;
+
 mod circle;
-pub(crate) use circle::{Circle, FilledCircle, HollowCircle};
+pub use circle::{
+    Circle, CircleArc, CircleIter, CirclePie, FilledCircle, FilledCircleIter, FilledCirclePie,
+    FilledCircleSpans, HollowCircle, HollowCirclePie,
+};
+
+mod curve;
+pub use curve::{
+    ArcLength, CatmullRom, CubicBezier, Curve, CurveIter, QuadraticBezier, DEFAULT_TOLERANCE,
+};
+
+mod ellipse;
+pub use ellipse::{Ellipse, EllipseIter, EllipseSpans, FilledEllipse, HollowEllipse};
 
 mod line;
-pub(crate) use line::Line;
+pub use line::{AntiAliasedLine, DashedLine, DashedLineIter, Line, LineIter, WuIter};
+
+mod path;
+pub use path::{Contour, FilledPath, Path, PathSegment, StrokedPath};
+
+mod polygon;
+pub use polygon::{
+    FillRule, FilledPolygon, HollowPolygon, OutlineIter, Polygon, PolygonSpans, Polyline,
+};
 
 mod rectangle;
-pub(crate) use rectangle::{FilledRectangle, HollowRectangle, Rectangle};
+pub use rectangle::{
+    FilledRectangle, FilledRectangleIter, FilledRectangleSpans, HollowRectangle, Rectangle,
+    RectangleIter,
+};
+
+mod rounded_rectangle;
+pub use rounded_rectangle::{
+    FilledRoundedRectangle, HollowRoundedRectangle, RoundedRectangle, RoundedRectangleIter,
+    RoundedRectangleSpans,
+};
+
+mod span;
+pub use span::{ExtentSpans, RowExtents, Span, SpanIter, SpanPixels};
+
+mod stroke;
+pub use stroke::{LineCap, LineJoin, Stroke, Stroked, StrokedIter};
+
+mod triangle;
+pub use triangle::{FilledTriangle, HollowTriangle, Triangle};
@@ -0,0 +1,695 @@
+use std::{
+    f32::consts::{PI, TAU},
+    io,
+};
+
+use crate::draw::{Drawable, SpanDrawable};
+
+use super::{
+    curve::{CubicBezier, Curve, QuadraticBezier, DEFAULT_TOLERANCE},
+    polygon::{push_contour, Edge, FillRule, PolygonSpans},
+    span::SpanPixels,
+    stroke::{Stroke, Stroked},
+};
+
+type Point = (f32, f32);
+
+/// A single drawing command of a path, in absolute coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSegment {
+    MoveTo(Point),
+    LineTo(Point),
+    QuadTo {
+        control: Point,
+        to: Point,
+    },
+    CubicTo {
+        control_from: Point,
+        control_to: Point,
+        to: Point,
+    },
+    /// An elliptical arc to the point, like the SVG arc command, the rotation is in radians
+    ArcTo {
+        radii: (f32, f32),
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    },
+    Close,
+}
+
+/// Outlines made of lines, curves and arcs, which can be stroked or filled.
+///
+/// Coordinates are in pixels, `(0.0, 0.0)` is the top-left corner of the first pixel,
+/// so a path around `(0.0, 0.0)` and `(4.0, 4.0)` fills 4x4 pixels.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+/// Building
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Starts a new contour at the point
+    pub fn move_to(mut self, to: Point) -> Self {
+        self.segments.push(PathSegment::MoveTo(to));
+        self
+    }
+
+    pub fn line_to(mut self, to: Point) -> Self {
+        self.segments.push(PathSegment::LineTo(to));
+        self
+    }
+
+    pub fn quad_to(mut self, control: Point, to: Point) -> Self {
+        self.segments.push(PathSegment::QuadTo { control, to });
+        self
+    }
+
+    pub fn cubic_to(mut self, control_from: Point, control_to: Point, to: Point) -> Self {
+        self.segments.push(PathSegment::CubicTo {
+            control_from,
+            control_to,
+            to,
+        });
+        self
+    }
+
+    /// Draws the part of an ellipse with the given radii and rotation, which ends at the point.
+    /// Of the four possible arcs `large_arc` picks the longer one and `sweep` the clockwise one.
+    pub fn arc_to(
+        mut self,
+        radii: (f32, f32),
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    ) -> Self {
+        self.segments.push(PathSegment::ArcTo {
+            radii,
+            rotation,
+            large_arc,
+            sweep,
+            to,
+        });
+        self
+    }
+
+    /// Connects the end of the current contour with its start
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+
+    /// Applies the function to every point, radii of arcs are scaled by `scale`
+    fn map(self, scale: f32, f: impl Fn(Point) -> Point) -> Self {
+        let segments = self
+            .segments
+            .into_iter()
+            .map(|segment| match segment {
+                PathSegment::MoveTo(to) => PathSegment::MoveTo(f(to)),
+                PathSegment::LineTo(to) => PathSegment::LineTo(f(to)),
+                PathSegment::QuadTo { control, to } => PathSegment::QuadTo {
+                    control: f(control),
+                    to: f(to),
+                },
+                PathSegment::CubicTo {
+                    control_from,
+                    control_to,
+                    to,
+                } => PathSegment::CubicTo {
+                    control_from: f(control_from),
+                    control_to: f(control_to),
+                    to: f(to),
+                },
+                PathSegment::ArcTo {
+                    radii,
+                    rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => PathSegment::ArcTo {
+                    radii: (radii.0 * scale, radii.1 * scale),
+                    rotation,
+                    large_arc,
+                    sweep,
+                    to: f(to),
+                },
+                PathSegment::Close => PathSegment::Close,
+            })
+            .collect();
+
+        Self { segments }
+    }
+
+    pub fn translated(self, offset: (f32, f32)) -> Self {
+        self.map(1.0, |(x, y)| (x + offset.0, y + offset.1))
+    }
+
+    /// Scales around the origin, e.g. to draw an icon at a different size
+    pub fn scaled(self, factor: f32) -> Self {
+        self.map(factor, |(x, y)| (x * factor, y * factor))
+    }
+
+    pub fn filled(self, fill_rule: FillRule) -> FilledPath {
+        FilledPath {
+            path: self,
+            fill_rule,
+        }
+    }
+
+    pub fn stroked(self, stroke: Stroke) -> StrokedPath {
+        StrokedPath { path: self, stroke }
+    }
+}
+
+/// A contour approximated with lines
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+/// Flattening
+impl Path {
+    /// Approximates all contours with lines, which are at most `tolerance` pixels off
+    pub fn flatten(&self, tolerance: f32) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut contour = Contour::default();
+        let mut current = (0.0, 0.0);
+
+        let mut finish = |contour: &mut Contour, closed: bool| {
+            let mut done = std::mem::take(contour);
+            done.closed = closed;
+            if done.points.len() > 1 {
+                contours.push(done);
+            }
+        };
+
+        for segment in self.segments.iter() {
+            if contour.points.is_empty() {
+                contour.points.push(current);
+            }
+
+            match *segment {
+                PathSegment::MoveTo(to) => {
+                    finish(&mut contour, false);
+                    contour.points.push(to);
+                    current = to;
+                }
+                PathSegment::LineTo(to) => {
+                    contour.points.push(to);
+                    current = to;
+                }
+                PathSegment::QuadTo { control, to } => {
+                    let curve = QuadraticBezier::new(current, control, to);
+                    extend(&mut contour, curve.flatten(tolerance));
+                    current = to;
+                }
+                PathSegment::CubicTo {
+                    control_from,
+                    control_to,
+                    to,
+                } => {
+                    let curve = CubicBezier::new(current, control_from, control_to, to);
+                    extend(&mut contour, curve.flatten(tolerance));
+                    current = to;
+                }
+                PathSegment::ArcTo {
+                    radii,
+                    rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => {
+                    flatten_arc(
+                        &mut contour.points,
+                        current,
+                        radii,
+                        rotation,
+                        large_arc,
+                        sweep,
+                        to,
+                        tolerance,
+                    );
+                    current = to;
+                }
+                PathSegment::Close => {
+                    current = contour.points[0];
+                    finish(&mut contour, true);
+                }
+            }
+        }
+        finish(&mut contour, false);
+
+        contours
+    }
+}
+
+/// Adds the flattened curve, skipping its start, which is the current point
+fn extend(contour: &mut Contour, points: Vec<(f32, Point)>) {
+    contour
+        .points
+        .extend(points.into_iter().skip(1).map(|(_, point)| point));
+}
+
+/// The signed angle from `u` to `v`
+fn angle_between(u: Point, v: Point) -> f32 {
+    let angle = (u.0 * v.1 - u.1 * v.0).atan2(u.0 * v.0 + u.1 * v.1);
+    if angle.is_nan() {
+        0.0
+    } else {
+        angle
+    }
+}
+
+/// Converts the arc from its end points to its center and samples it,
+/// following the implementation notes of the SVG specification
+#[allow(clippy::too_many_arguments)]
+fn flatten_arc(
+    points: &mut Vec<Point>,
+    from: Point,
+    radii: (f32, f32),
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+    tolerance: f32,
+) {
+    let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+    if from == to {
+        return;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        points.push(to);
+        return;
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+    let (hx, hy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * hx + sin * hy, -sin * hx + cos * hy);
+
+    // radii too small to reach the end point are scaled up
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let (cx1, cy1) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+
+    let center = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+    let start_vector = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let end_vector = ((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+    let start = angle_between((1.0, 0.0), start_vector);
+    let mut delta = angle_between(start_vector, end_vector);
+    if !sweep && delta > 0.0 {
+        delta -= TAU;
+    } else if sweep && delta < 0.0 {
+        delta += TAU;
+    }
+
+    // the angle per line, so the chord stays within the tolerance of the arc
+    let radius = rx.max(ry);
+    let step = 2.0 * (1.0 - tolerance.max(0.01) / radius).max(-1.0).acos();
+    let count = (delta.abs() / step.max(PI / 180.0)).ceil().max(1.0) as usize;
+
+    for i in 1..count {
+        let angle = start + delta * i as f32 / count as f32;
+        let (angle_sin, angle_cos) = angle.sin_cos();
+        points.push((
+            center.0 + rx * cos * angle_cos - ry * sin * angle_sin,
+            center.1 + rx * sin * angle_cos + ry * cos * angle_sin,
+        ));
+    }
+    points.push(to);
+}
+
+fn to_stroke_space(points: &[Point]) -> Vec<(f64, f64)> {
+    points.iter().map(|&(x, y)| (x as f64, y as f64)).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilledPath {
+    pub path: Path,
+    pub fill_rule: FillRule,
+}
+
+impl Drawable for FilledPath {
+    type IntoIter = SpanPixels<PolygonSpans>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+/// Every contour is closed for filling
+impl SpanDrawable for FilledPath {
+    type Spans = PolygonSpans;
+
+    fn spans(&self) -> Self::Spans {
+        let mut edges: Vec<Edge> = Vec::new();
+        for contour in self.path.flatten(DEFAULT_TOLERANCE) {
+            push_contour(&mut edges, &to_stroke_space(&contour.points));
+        }
+
+        PolygonSpans::new(edges, self.fill_rule)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrokedPath {
+    pub path: Path,
+    pub stroke: Stroke,
+}
+
+impl Drawable for StrokedPath {
+    type IntoIter = SpanPixels<PolygonSpans>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+/// Overlapping contours are merged, so no pixel is drawn twice
+impl SpanDrawable for StrokedPath {
+    type Spans = PolygonSpans;
+
+    fn spans(&self) -> Self::Spans {
+        let edges = self
+            .path
+            .flatten(DEFAULT_TOLERANCE)
+            .into_iter()
+            .flat_map(|contour| {
+                let points = to_stroke_space(&contour.points);
+                Stroked::new(points, contour.closed, self.stroke).edges()
+            })
+            .collect();
+
+        PolygonSpans::new(edges, FillRule::NonZero)
+    }
+}
+
+/// Reads the numbers and flags of an SVG path
+struct SvgParser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SvgParser<'a> {
+    fn error(&self, message: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("position {}: {}", self.position, message),
+        )
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.data.get(self.position) {
+            if c.is_ascii_whitespace() || *c == b',' {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.position >= self.data.len()
+    }
+
+    /// Whether the next token is a number, continuing the previous command
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(
+            self.data.get(self.position),
+            Some(c) if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.')
+        )
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let command = *self.data.get(self.position)?;
+        if command.is_ascii_alphabetic() {
+            self.position += 1;
+            Some(command)
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> io::Result<f32> {
+        self.skip_separators();
+        let start = self.position;
+        let digits = |parser: &mut Self| {
+            while matches!(parser.data.get(parser.position), Some(c) if c.is_ascii_digit()) {
+                parser.position += 1;
+            }
+        };
+
+        if matches!(self.data.get(self.position), Some(b'-') | Some(b'+')) {
+            self.position += 1;
+        }
+        digits(self);
+        if self.data.get(self.position) == Some(&b'.') {
+            self.position += 1;
+            digits(self);
+        }
+        if matches!(self.data.get(self.position), Some(b'e') | Some(b'E')) {
+            self.position += 1;
+            if matches!(self.data.get(self.position), Some(b'-') | Some(b'+')) {
+                self.position += 1;
+            }
+            digits(self);
+        }
+
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| {
+                self.position = start;
+                self.error("expected a number")
+            })
+    }
+
+    fn point(&mut self) -> io::Result<Point> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    /// Flags are a single digit and need no separator
+    fn flag(&mut self) -> io::Result<bool> {
+        self.skip_separators();
+        let flag = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error("expected a flag")),
+        };
+        self.position += 1;
+
+        Ok(flag)
+    }
+}
+
+/// SVG import
+impl Path {
+    /// Parses the `d` attribute of an SVG path element
+    pub fn from_svg(d: &str) -> io::Result<Self> {
+        let mut parser = SvgParser {
+            data: d.as_bytes(),
+            position: 0,
+        };
+        let mut path = Path::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        // the last control point, which is reflected by smooth curves
+        let mut last_control: Option<(u8, Point)> = None;
+        let mut command = None;
+
+        while !parser.at_end() {
+            command = match parser.command() {
+                Some(next) => Some(next),
+                // numbers without a command repeat the previous one
+                None if command.is_some() && parser.has_number() => command,
+                None => return Err(parser.error("expected a command")),
+            };
+            let name = command.unwrap_or_default();
+            let relative = name.is_ascii_lowercase();
+            let offset = if relative { current } else { (0.0, 0.0) };
+            let absolute = |(x, y): Point| (x + offset.0, y + offset.1);
+            let reflected = |kind: u8| match last_control {
+                Some((last, control)) if last == kind => {
+                    (2.0 * current.0 - control.0, 2.0 * current.1 - control.1)
+                }
+                _ => current,
+            };
+
+            let mut control = None;
+            match name.to_ascii_uppercase() {
+                b'M' => {
+                    current = absolute(parser.point()?);
+                    start = current;
+                    path = path.move_to(current);
+                    // following points are lines
+                    command = Some(if relative { b'l' } else { b'L' });
+                }
+                b'L' => {
+                    current = absolute(parser.point()?);
+                    path = path.line_to(current);
+                }
+                b'H' => {
+                    let x = parser.number()? + offset.0;
+                    current = (x, current.1);
+                    path = path.line_to(current);
+                }
+                b'V' => {
+                    let y = parser.number()? + offset.1;
+                    current = (current.0, y);
+                    path = path.line_to(current);
+                }
+                b'Q' => {
+                    let quad_control = absolute(parser.point()?);
+                    current = absolute(parser.point()?);
+                    path = path.quad_to(quad_control, current);
+                    control = Some((b'Q', quad_control));
+                }
+                b'T' => {
+                    let quad_control = reflected(b'Q');
+                    current = absolute(parser.point()?);
+                    path = path.quad_to(quad_control, current);
+                    control = Some((b'Q', quad_control));
+                }
+                b'C' => {
+                    let control_from = absolute(parser.point()?);
+                    let control_to = absolute(parser.point()?);
+                    current = absolute(parser.point()?);
+                    path = path.cubic_to(control_from, control_to, current);
+                    control = Some((b'C', control_to));
+                }
+                b'S' => {
+                    let control_from = reflected(b'C');
+                    let control_to = absolute(parser.point()?);
+                    current = absolute(parser.point()?);
+                    path = path.cubic_to(control_from, control_to, current);
+                    control = Some((b'C', control_to));
+                }
+                b'A' => {
+                    let radii = parser.point()?;
+                    let rotation = parser.number()?.to_radians();
+                    let large_arc = parser.flag()?;
+                    let sweep = parser.flag()?;
+                    current = absolute(parser.point()?);
+                    path = path.arc_to(radii, rotation, large_arc, sweep, current);
+                }
+                b'Z' => {
+                    current = start;
+                    path = path.close();
+                    command = None;
+                }
+                _ => {
+                    parser.position -= 1;
+                    return Err(parser.error(&format!("unknown command '{}'", name as char)));
+                }
+            }
+            last_control = control;
+        }
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        pixel::Pixel,
+        primitives::{Rectangle, Span},
+    };
+
+    #[test]
+    fn filled_square_matches_rectangle() {
+        // Arrange
+        let square = Path::from_svg("M2 3 h6 v4 H2 z").unwrap();
+        let built = Path::new()
+            .move_to((2.0, 3.0))
+            .line_to((8.0, 3.0))
+            .line_to((8.0, 7.0))
+            .line_to((2.0, 7.0))
+            .close();
+
+        // Act
+        let spans: Vec<Span> = square.clone().filled(FillRule::NonZero).spans().collect();
+        let expected: Vec<Span> = Rectangle::new((2, 3).into(), 6, 4)
+            .filled()
+            .spans()
+            .collect();
+
+        // Assert
+        assert_eq!(square, built);
+        assert_eq!(spans, expected);
+    }
+
+    #[test]
+    fn parse_repeated_and_smooth_commands() {
+        // Arrange
+        let d = "m10,10 20-5 .5.5 s10 10 20 0 A5 5 0 1 1 0 0Z";
+
+        // Act
+        let path = Path::from_svg(d).unwrap();
+
+        // Assert
+        assert_eq!(
+            path.segments()[..3],
+            [
+                PathSegment::MoveTo((10.0, 10.0)),
+                PathSegment::LineTo((30.0, 5.0)),
+                PathSegment::LineTo((30.5, 5.5)),
+            ]
+        );
+        assert_eq!(
+            path.segments()[3],
+            PathSegment::CubicTo {
+                control_from: (30.5, 5.5),
+                control_to: (40.5, 15.5),
+                to: (50.5, 5.5),
+            }
+        );
+        assert_eq!(path.segments().len(), 6);
+        assert!(Path::from_svg("M0 0 L10").is_err());
+        assert!(Path::from_svg("M0 0 X10 10").is_err());
+    }
+
+    #[test]
+    fn arcs_and_holes() {
+        // Arrange, a ring made of two circles with opposite directions
+        let ring = Path::from_svg(
+            "M0 10 a10 10 0 1 0 20 0 a10 10 0 1 0 -20 0 z \
+             M5 10 a5 5 0 1 1 10 0 a5 5 0 1 1 -10 0 z",
+        )
+        .unwrap()
+        .scaled(2.0);
+
+        // Act
+        let pixels: Vec<Pixel> = ring.filled(FillRule::NonZero).pixels().collect();
+
+        // Assert
+        assert!(pixels.contains(&(4, 20).into()));
+        assert!(!pixels.contains(&(20, 20).into()));
+        assert!(!pixels.contains(&(40, 20).into()));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    bounds::{bounding_box, HitTest},
+    line::LineIter,
+    rectangle::Rectangle,
+    span::{Span, SpanPixels},
+    stroke::{Stroke, Stroked},
+};
+
+/// Decides which areas of a self-intersecting polygon are inside
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if a ray from the point crosses the outline an odd number of times
+    EvenOdd,
+    /// Inside if the outline winds around the point at least once
+    NonZero,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub vertices: Vec<Pixel>,
+    pub fill_rule: FillRule,
+}
+
+impl Polygon {
+    /// Uses the non-zero fill rule
+    pub fn new(vertices: Vec<Pixel>) -> Self {
+        Self {
+            vertices,
+            fill_rule: FillRule::NonZero,
+        }
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    pub fn hollow(self) -> HollowPolygon {
+        HollowPolygon(self)
+    }
+
+    pub fn filled(self) -> FilledPolygon {
+        FilledPolygon(self)
+    }
+
+    /// Strokes the closed outline through the vertex pixels
+    pub fn stroked(&self, stroke: Stroke) -> Stroked {
+        Stroked::through_pixels(&self.vertices, true, stroke)
+    }
+}
+
+/// A chain of connected lines, which is not closed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    pub vertices: Vec<Pixel>,
+}
+
+impl Polyline {
+    pub fn new(vertices: Vec<Pixel>) -> Self {
+        Self { vertices }
+    }
+
+    pub fn stroked(&self, stroke: Stroke) -> Stroked {
+        Stroked::through_pixels(&self.vertices, false, stroke)
+    }
+}
+
+/// Lazily walks the lines between consecutive vertices
+pub struct OutlineIter<V> {
+    vertices: V,
+    closed: bool,
+    index: usize,
+    line: Option<LineIter>,
+}
+
+impl<V: AsRef<[Pixel]>> OutlineIter<V> {
+    pub(crate) fn new(vertices: V, closed: bool) -> Self {
+        Self {
+            vertices,
+            closed,
+            index: 0,
+            line: None,
+        }
+    }
+}
+
+impl<V: AsRef<[Pixel]>> Iterator for OutlineIter<V> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pixel) = self.line.as_mut().and_then(|l| l.next()) {
+                return Some(pixel);
+            }
+
+            let vertices = self.vertices.as_ref();
+            let lines = if self.closed {
+                vertices.len()
+            } else {
+                vertices.len().saturating_sub(1)
+            };
+
+            if self.index >= lines {
+                // lines exclude their end, so an open chain still misses its last vertex
+                if !self.closed && self.index == lines && !vertices.is_empty() {
+                    self.index += 1;
+                    return vertices.last().copied();
+                }
+                return None;
+            }
+
+            let from = vertices[self.index];
+            let to = vertices[(self.index + 1) % vertices.len()];
+            self.line = Some(LineIter::new(from, to));
+            self.index += 1;
+        }
+    }
+}
+
+/// A straight part of a polygon outline
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Edge {
+    from: (f64, f64),
+    to: (f64, f64),
+}
+
+/// Adds the closed outline through the given points to the edges, skipping horizontal edges
+pub(crate) fn push_contour(edges: &mut Vec<Edge>, points: &[(f64, f64)]) {
+    for (i, &from) in points.iter().enumerate() {
+        let to = points[(i + 1) % points.len()];
+        if from.1 != to.1 {
+            edges.push(Edge { from, to });
+        }
+    }
+}
+
+/// Vertices are the corners of pixels, so a polygon from `(0, 0)` to `(4, 4)` covers 4x4 pixels
+pub(crate) fn pixel_corners(vertices: &[Pixel]) -> Vec<(f64, f64)> {
+    vertices
+        .iter()
+        .map(|v| (v.x() as f64, v.y() as f64))
+        .collect()
+}
+
+/// The rectangle around the corners of all vertices
+pub(crate) fn corners_bounding_box(vertices: &[Pixel]) -> Option<Rectangle> {
+    let xs = vertices.iter().map(|v| v.x() as i64);
+    let ys = vertices.iter().map(|v| v.y() as i64);
+
+    bounding_box(xs.clone().min()?, ys.clone().min()?, xs.max()?, ys.max()?)
+}
+
+/// Tests the center of the pixel like [`PolygonSpans`] does,
+/// counting the crossings left of it on its row
+pub(crate) fn corners_contain(vertices: &[Pixel], fill_rule: FillRule, pixel: Pixel) -> bool {
+    let (center_x, center_y) = (pixel.x() as f64 + 0.5, pixel.y() as f64 + 0.5);
+    let corners = pixel_corners(vertices);
+
+    let mut winding = 0;
+    let mut crossings = 0;
+    for (i, &(x0, y0)) in corners.iter().enumerate() {
+        let (x1, y1) = corners[(i + 1) % corners.len()];
+
+        if (y0 <= center_y && center_y < y1) || (y1 <= center_y && center_y < y0) {
+            let x = x0 + (center_y - y0) * (x1 - x0) / (y1 - y0);
+            if x <= center_x {
+                winding += if y1 > y0 { 1 } else { -1 };
+                crossings += 1;
+            }
+        }
+    }
+
+    match fill_rule {
+        FillRule::EvenOdd => crossings % 2 == 1,
+        FillRule::NonZero => winding != 0,
+    }
+}
+
+/// Lazily scan converts polygons into spans.
+///
+/// Pixels are covered if their center lies inside the outline,
+/// edges are treated as half open, including the top and left and excluding the bottom and right.
+/// That way polygons sharing an edge never draw the same pixel twice.
+pub struct PolygonSpans {
+    edges: Vec<Edge>,
+    fill_rule: FillRule,
+    row: i64,
+    end_row: i64,
+    crossings: Vec<(f64, i32)>,
+    pending: Vec<Span>,
+}
+
+impl PolygonSpans {
+    /// Fills the area enclosed by the edges, which may come from several contours
+    pub(crate) fn new(edges: Vec<Edge>, fill_rule: FillRule) -> Self {
+        let (top, bottom) =
+            edges
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(top, bottom), e| {
+                    (
+                        top.min(e.from.1).min(e.to.1),
+                        bottom.max(e.from.1).max(e.to.1),
+                    )
+                });
+
+        let (row, end_row) = if edges.is_empty() {
+            (0, 0)
+        } else {
+            ((top - 0.5).ceil() as i64, (bottom - 0.5).ceil() as i64)
+        };
+
+        Self {
+            edges,
+            fill_rule,
+            row,
+            end_row,
+            crossings: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub(crate) fn from_points(points: &[(f64, f64)], fill_rule: FillRule) -> Self {
+        let mut edges = Vec::with_capacity(points.len());
+        push_contour(&mut edges, points);
+
+        Self::new(edges, fill_rule)
+    }
+
+    /// Collects the spans of the current row into pending
+    fn scan_row(&mut self) {
+        let center_y = self.row as f64 + 0.5;
+
+        self.crossings.clear();
+        for edge in self.edges.iter() {
+            let ((x0, y0), (x1, y1)) = (edge.from, edge.to);
+
+            if (y0 <= center_y && center_y < y1) || (y1 <= center_y && center_y < y0) {
+                let x = x0 + (center_y - y0) * (x1 - x0) / (y1 - y0);
+                let winding = if y1 > y0 { 1 } else { -1 };
+                self.crossings.push((x, winding));
+            }
+        }
+
+        self.crossings
+            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        // a pixel is inside, if its center lies between the entering and leaving crossing
+        let row = self.row;
+        let pending = &mut self.pending;
+        let mut push = |start: f64, end: f64| {
+            let start = (start - 0.5).ceil() as i64;
+            let end = (end - 0.5).ceil() as i64;
+            if let Some(span) = Span::clipped(row, start, end) {
+                pending.push(span);
+            }
+        };
+
+        match self.fill_rule {
+            FillRule::EvenOdd => {
+                for pair in self.crossings.chunks_exact(2) {
+                    push(pair[0].0, pair[1].0);
+                }
+            }
+            FillRule::NonZero => {
+                let mut winding = 0;
+                let mut start = 0.0;
+                for &(x, direction) in self.crossings.iter() {
+                    if winding == 0 {
+                        start = x;
+                    }
+                    winding += direction;
+                    if winding == 0 {
+                        push(start, x);
+                    }
+                }
+            }
+        }
+
+        // pending is consumed from the back
+        pending.reverse();
+    }
+}
+
+impl Iterator for PolygonSpans {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(span) = self.pending.pop() {
+                return Some(span);
+            }
+
+            if self.row >= self.end_row {
+                return None;
+            }
+
+            self.scan_row();
+            self.row += 1;
+        }
+    }
+}
+
+impl HitTest for Polygon {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        corners_bounding_box(&self.vertices)
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        corners_contain(&self.vertices, self.fill_rule, pixel)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HollowPolygon(pub Polygon);
+impl Drawable for HollowPolygon {
+    type IntoIter = OutlineIter<Vec<Pixel>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        OutlineIter::new(self.0.vertices.clone(), true)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilledPolygon(pub Polygon);
+impl Drawable for FilledPolygon {
+    type IntoIter = SpanPixels<PolygonSpans>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledPolygon {
+    type Spans = PolygonSpans;
+
+    fn spans(&self) -> Self::Spans {
+        PolygonSpans::from_points(&pixel_corners(&self.0.vertices), self.0.fill_rule)
+    }
+}
+
+impl Drawable for Polyline {
+    type IntoIter = OutlineIter<Vec<Pixel>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        OutlineIter::new(self.vertices.clone(), false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Rectangle;
+
+    #[test]
+    fn square_matches_rectangle() {
+        // Arrange
+        let square = Polygon::new(vec![
+            (2, 3).into(),
+            (8, 3).into(),
+            (8, 7).into(),
+            (2, 7).into(),
+        ])
+        .filled();
+        let rect = Rectangle::new((2, 3).into(), 6, 4).filled();
+
+        // Act
+        let expected: Vec<Span> = rect.spans().collect();
+        let actual: Vec<Span> = square.spans().collect();
+
+        // Assert
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fill_rules() {
+        // Arrange, a pentagram, whose center is wound around twice
+        let star = Polygon::new(vec![
+            (50, 0).into(),
+            (79, 90).into(),
+            (2, 35).into(),
+            (97, 35).into(),
+            (21, 90).into(),
+        ]);
+        let center: Pixel = (50, 50).into();
+
+        // Act
+        let non_zero: Vec<Pixel> = star.clone().filled().pixels().collect();
+        let even_odd: Vec<Pixel> = star
+            .with_fill_rule(FillRule::EvenOdd)
+            .filled()
+            .pixels()
+            .collect();
+
+        // Assert
+        assert!(non_zero.contains(&center));
+        assert!(!even_odd.contains(&center));
+    }
+
+    #[test]
+    fn contains_matches_fill() {
+        for fill_rule in [FillRule::NonZero, FillRule::EvenOdd].iter().copied() {
+            // Arrange
+            let star = Polygon::new(vec![
+                (50, 0).into(),
+                (79, 90).into(),
+                (2, 35).into(),
+                (97, 35).into(),
+                (21, 90).into(),
+            ])
+            .with_fill_rule(fill_rule);
+
+            // Act
+            let filled: Vec<Pixel> = star.clone().filled().pixels().collect();
+            let bb = star.bounding_box().unwrap();
+            let contained: Vec<Pixel> =
+                bb.filled().pixels().filter(|p| star.contains(*p)).collect();
+
+            // Assert
+            assert_eq!(bb, Rectangle::new((2, 0).into(), 95, 90));
+            assert_eq!(filled, contained);
+        }
+    }
+
+    #[test]
+    fn polyline_includes_last_vertex() {
+        let line = Polyline::new(vec![(0, 0).into(), (2, 0).into(), (2, 2).into()]);
+
+        let pixels: Vec<Pixel> = line.pixels().collect();
+
+        assert_eq!(pixels.len(), 5);
+        assert_eq!(pixels.last(), Some(&(2, 2).into()));
+    }
+}
@@ -1,67 +1,165 @@
-use crate::{draw::Drawable, pixel::Pixel};
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
 
-use super::Line;
+use super::{
+    bounds::{bounding_box, HitTest},
+    line::LineIter,
+    span::{Span, SpanPixels},
+};
 
-pub(crate) struct Rectangle {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rectangle {
     pub top_left: Pixel,
     pub width: u32,
     pub height: u32,
 }
 
-fn rect_pixels(rect: &Rectangle, filled: bool) -> Vec<Pixel> {
-    let corners = vec![
-        rect.top_left,
-        rect.top_left + (rect.width, 0),
-        rect.top_left + (rect.width, rect.height),
-        rect.top_left + (0, rect.height),
-    ];
-
-    let mut pixels: Vec<Pixel> = Vec::new();
-
-    if !filled {
-        // Just draw lines between all corner points
-        for (cur, next) in corners
-            .iter()
-            .zip(corners.iter().skip(1).chain(corners.iter().take(1)))
-        {
-            pixels.extend(
-                Line {
-                    from: *cur,
-                    to: *next,
-                }
-                .pixels(),
-            );
+impl Rectangle {
+    pub fn new(top_left: Pixel, width: u32, height: u32) -> Self {
+        Self {
+            top_left,
+            width,
+            height,
         }
-    } else {
-        // Horizontal scan-lines from top left to top right offsetting on each iteration
-        for y_offset in 0..rect.height {
-            pixels.extend(
-                Line {
-                    from: corners[0] + (0, y_offset),
-                    to: corners[1] + (0, y_offset),
-                }
-                .pixels(),
-            );
+    }
+
+    /// Tests if the pixel lies in the area covered by the filled rectangle
+    pub fn contains(&self, pixel: Pixel) -> bool {
+        pixel.x() >= self.top_left.x()
+            && pixel.y() >= self.top_left.y()
+            && pixel.x() - self.top_left.x() < self.width
+            && pixel.y() - self.top_left.y() < self.height
+    }
+
+    /// The area covered by both rectangles
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let (a, b) = (self.extents(), other.extents());
+
+        bounding_box(a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3))
+    }
+
+    pub fn hollow(self) -> HollowRectangle {
+        HollowRectangle(self)
+    }
+
+    pub fn filled(self) -> FilledRectangle {
+        FilledRectangle(self)
+    }
+}
+
+impl Rectangle {
+    /// Left, top and the exclusive right, bottom
+    fn extents(&self) -> (i64, i64, i64, i64) {
+        let (x, y) = (self.top_left.x() as i64, self.top_left.y() as i64);
+
+        (x, y, x + self.width as i64, y + self.height as i64)
+    }
+
+    fn corners(&self) -> [Pixel; 4] {
+        [
+            self.top_left,
+            self.top_left + (self.width, 0),
+            self.top_left + (self.width, self.height),
+            self.top_left + (0, self.height),
+        ]
+    }
+}
+
+impl HitTest for Rectangle {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let (left, top, right, bottom) = self.extents();
+        bounding_box(left, top, right, bottom)
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        Rectangle::contains(self, pixel)
+    }
+}
+
+/// Lazily walks the lines between all corner points
+pub struct RectangleIter {
+    corners: [Pixel; 4],
+    side: usize,
+    line: Option<LineIter>,
+}
+
+impl Iterator for RectangleIter {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pixel) = self.line.as_mut().and_then(|l| l.next()) {
+                return Some(pixel);
+            }
+
+            if self.side >= self.corners.len() {
+                return None;
+            }
+
+            let from = self.corners[self.side];
+            let to = self.corners[(self.side + 1) % self.corners.len()];
+            self.line = Some(LineIter::new(from, to));
+            self.side += 1;
         }
     }
+}
 
-    pixels
+/// Lazily yields horizontal scan-lines from top to bottom
+pub struct FilledRectangleSpans {
+    rect: Rectangle,
+    row: u32,
 }
 
-pub(crate) struct HollowRectangle(pub Rectangle);
+impl Iterator for FilledRectangleSpans {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row >= self.rect.height || self.rect.width == 0 {
+            return None;
+        }
+
+        let left = self.rect.top_left + (0, self.row);
+        self.row += 1;
+
+        Some(Span::new(left.y(), left.x(), left.x() + self.rect.width))
+    }
+}
+
+pub type FilledRectangleIter = SpanPixels<FilledRectangleSpans>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowRectangle(pub Rectangle);
 impl Drawable for HollowRectangle {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+    type IntoIter = RectangleIter;
 
     fn pixels(&self) -> Self::IntoIter {
-        rect_pixels(&self.0, false).into_iter()
+        RectangleIter {
+            corners: self.0.corners(),
+            side: 0,
+            line: None,
+        }
     }
 }
 
-pub(crate) struct FilledRectangle(pub Rectangle);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledRectangle(pub Rectangle);
 impl Drawable for FilledRectangle {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+    type IntoIter = FilledRectangleIter;
 
     fn pixels(&self) -> Self::IntoIter {
-        rect_pixels(&self.0, true).into_iter()
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledRectangle {
+    type Spans = FilledRectangleSpans;
+
+    fn spans(&self) -> Self::Spans {
+        FilledRectangleSpans {
+            rect: self.0,
+            row: 0,
+        }
     }
 }
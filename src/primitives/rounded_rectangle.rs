@@ -0,0 +1,146 @@
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    bounds::{extents_bounding_box, extents_contain, HitTest},
+    rectangle::Rectangle,
+    span::{ExtentSpans, RowExtents, SpanPixels},
+};
+
+/// A rectangle with quarter circles as corners
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundedRectangle {
+    pub rect: Rectangle,
+    pub radius: u32,
+}
+
+impl RoundedRectangle {
+    pub fn new(top_left: Pixel, width: u32, height: u32, radius: u32) -> Self {
+        Self {
+            rect: Rectangle::new(top_left, width, height),
+            radius,
+        }
+    }
+
+    /// The corner radius, limited to half of the shorter side
+    fn corner_radius(&self) -> u32 {
+        self.radius
+            .min(self.rect.width / 2)
+            .min(self.rect.height / 2)
+    }
+
+    pub fn hollow(self) -> HollowRoundedRectangle {
+        HollowRoundedRectangle(self)
+    }
+
+    pub fn filled(self) -> FilledRoundedRectangle {
+        FilledRoundedRectangle(self)
+    }
+}
+
+/// Covers the same area as the filled [`Rectangle`] minus the pixels,
+/// whose centers lie outside of the corners
+impl RowExtents for RoundedRectangle {
+    fn rows(&self) -> (i64, i64) {
+        let top = self.rect.top_left.y() as i64;
+
+        (top, top + self.rect.height as i64 - 1)
+    }
+
+    fn extents(&self, y: i64) -> Option<(i64, i64)> {
+        let row = y - self.rect.top_left.y() as i64;
+        let height = self.rect.height as i64;
+        if row < 0 || row >= height || self.rect.width == 0 {
+            return None;
+        }
+
+        let radius = self.corner_radius() as i64;
+        // vertical distance of the pixel center to the center of the corner
+        let dy = if row < radius {
+            (radius - row) as f64 - 0.5
+        } else if row >= height - radius {
+            (row - (height - radius)) as f64 + 0.5
+        } else {
+            0.0
+        };
+
+        let inset = if dy > 0.0 {
+            let radius = radius as f64;
+            let half_width = (radius * radius - dy * dy).max(0.0).sqrt();
+            (radius - half_width - 0.5).ceil().max(0.0) as i64
+        } else {
+            0
+        };
+
+        let left = self.rect.top_left.x() as i64;
+        Some((left + inset, left + self.rect.width as i64 - inset))
+    }
+}
+
+impl HitTest for RoundedRectangle {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        extents_bounding_box(self)
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        extents_contain(self, pixel)
+    }
+}
+
+pub type RoundedRectangleSpans = ExtentSpans<RoundedRectangle>;
+pub type RoundedRectangleIter = SpanPixels<RoundedRectangleSpans>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowRoundedRectangle(pub RoundedRectangle);
+impl Drawable for HollowRoundedRectangle {
+    type IntoIter = RoundedRectangleIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(ExtentSpans::new(self.0, true))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledRoundedRectangle(pub RoundedRectangle);
+impl Drawable for FilledRoundedRectangle {
+    type IntoIter = RoundedRectangleIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledRoundedRectangle {
+    type Spans = RoundedRectangleSpans;
+
+    fn spans(&self) -> Self::Spans {
+        ExtentSpans::new(self.0, false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::Span;
+
+    #[test]
+    fn corners_are_cut() {
+        // Arrange
+        let square = RoundedRectangle::new((10, 10).into(), 20, 12, 0).filled();
+        let rounded = RoundedRectangle::new((10, 10).into(), 20, 12, 4).filled();
+        let rect = Rectangle::new((10, 10).into(), 20, 12).filled();
+
+        // Act
+        let square: Vec<Span> = square.spans().collect();
+        let rounded: Vec<Span> = rounded.spans().collect();
+
+        // Assert
+        assert_eq!(square, rect.spans().collect::<Vec<Span>>());
+        assert_eq!(rounded.len(), 12);
+        assert!(rounded[0].start > 10 && rounded[0].end < 30);
+        assert_eq!(rounded[6], Span::new(16, 10, 30));
+        assert_eq!(rounded[0].start - 10, 30 - rounded[0].end);
+    }
+}
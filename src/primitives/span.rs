@@ -0,0 +1,185 @@
+use crate::pixel::Pixel;
+
+/// A horizontal run of pixels on row `y`, from `start` to the exclusive `end`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub y: u32,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub fn new(y: u32, start: u32, end: u32) -> Self {
+        Self { y, start, end }
+    }
+
+    /// Creates a span from signed coordinates, cutting off everything left of or above zero
+    pub(crate) fn clipped(y: i64, start: i64, end: i64) -> Option<Self> {
+        if y < 0 || end <= 0 || end <= start {
+            return None;
+        }
+
+        Some(Self::new(y as u32, start.max(0) as u32, end as u32))
+    }
+
+    pub fn len(&self) -> u32 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl IntoIterator for Span {
+    type Item = Pixel;
+    type IntoIter = SpanIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SpanIter { span: self }
+    }
+}
+
+/// Lazily yields the pixels of a span from left to right
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpanIter {
+    span: Span,
+}
+
+impl Iterator for SpanIter {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.span.is_empty() {
+            return None;
+        }
+
+        let pixel = Pixel((self.span.start, self.span.y));
+        self.span.start += 1;
+
+        Some(pixel)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.span.len() as usize;
+        (len, Some(len))
+    }
+}
+
+/// Flattens spans into their pixels, so span shapes can be drawn as any other Drawable
+pub struct SpanPixels<S> {
+    spans: S,
+    current: SpanIter,
+}
+
+impl<S: Iterator<Item = Span>> SpanPixels<S> {
+    pub(crate) fn new(spans: S) -> Self {
+        Self {
+            spans,
+            current: SpanIter::default(),
+        }
+    }
+}
+
+impl<S: Iterator<Item = Span>> Iterator for SpanPixels<S> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pixel) = self.current.next() {
+                return Some(pixel);
+            }
+
+            self.current = self.spans.next()?.into_iter();
+        }
+    }
+}
+
+/// A shape covering a single contiguous run on each of its rows
+pub trait RowExtents {
+    /// The first and last row, both inclusive
+    fn rows(&self) -> (i64, i64);
+    /// The start and exclusive end of the run on the given row
+    fn extents(&self, y: i64) -> Option<(i64, i64)>;
+}
+
+/// Lazily yields the rows of a shape, either completely or only its outline.
+///
+/// The outline of a row are the pixels at its ends,
+/// which are not covered by both the row above and the row below.
+pub struct ExtentSpans<S> {
+    shape: S,
+    row: i64,
+    last_row: i64,
+    outline: bool,
+    pending: Option<Span>,
+}
+
+impl<S: RowExtents> ExtentSpans<S> {
+    pub(crate) fn new(shape: S, outline: bool) -> Self {
+        let (row, last_row) = shape.rows();
+
+        Self {
+            shape,
+            row,
+            last_row,
+            outline,
+            pending: None,
+        }
+    }
+
+    fn outline(&mut self, y: i64, (start, end): (i64, i64)) -> Option<Span> {
+        let above = self.shape.extents(y - 1);
+        let below = self.shape.extents(y + 1);
+
+        let inner = match (above, below) {
+            (Some(above), Some(below)) => Some((
+                above.0.max(below.0).max(start + 1),
+                above.1.min(below.1).min(end - 1),
+            )),
+            _ => None,
+        };
+
+        match inner {
+            Some((inner_start, inner_end)) if inner_start < inner_end => {
+                self.pending = Span::clipped(y, inner_end, end);
+                Span::clipped(y, start, inner_start)
+            }
+            _ => Span::clipped(y, start, end),
+        }
+    }
+}
+
+impl<S: RowExtents> Iterator for ExtentSpans<S> {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(span) = self.pending.take() {
+                return Some(span);
+            }
+
+            if self.row > self.last_row {
+                return None;
+            }
+
+            let y = self.row;
+            self.row += 1;
+
+            let extents = match self.shape.extents(y) {
+                Some(extents) => extents,
+                None => continue,
+            };
+
+            let span = if self.outline {
+                self.outline(y, extents)
+            } else {
+                Span::clipped(y, extents.0, extents.1)
+            };
+
+            if span.is_some() {
+                return span;
+            }
+        }
+    }
+}
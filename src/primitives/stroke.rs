@@ -0,0 +1,471 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    polygon::{push_contour, Edge, FillRule, PolygonSpans},
+    span::SpanPixels,
+};
+
+/// How the open ends of a stroke look
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// Ends exactly at the end point
+    Butt,
+    /// Extends past the end point by half the width
+    Square,
+    /// Ends in a half circle around the end point
+    Round,
+}
+
+/// How two segments of a stroke are connected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Extends the outer edges until they meet, falls back to bevel for sharp angles
+    Miter,
+    /// Connects the outer corners with a straight edge
+    Bevel,
+    /// Rounds the outer corner with a circle
+    Round,
+}
+
+/// The longest miter, relative to the stroke width, before falling back to a bevel
+const MITER_LIMIT: f64 = 4.0;
+
+/// Describes how lines are drawn with a width
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Each bit decides if one pixel along the line is drawn, starting at the highest bit.
+    /// The pattern repeats every 32 pixels, `u32::MAX` draws a solid line.
+    pub pattern: u32,
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            pattern: u32::MAX,
+        }
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: u32) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Whether the pattern draws the pixel at the given distance along the line
+    fn is_on(&self, distance: f64) -> bool {
+        let bit = (distance.floor() as i64).rem_euclid(32);
+        self.pattern & (1 << (31 - bit)) != 0
+    }
+
+    /// The drawn runs of a segment from `start` to `end` distance along the line
+    fn dashes(&self, start: f64, end: f64) -> Vec<(f64, f64)> {
+        match self.pattern {
+            u32::MAX => return vec![(start, end)],
+            0 => return Vec::new(),
+            _ => (),
+        }
+
+        let mut dashes: Vec<(f64, f64)> = Vec::new();
+        let mut position = start;
+        while position < end {
+            let next = (position.floor() + 1.0).min(end);
+            if self.is_on(position) {
+                match dashes.last_mut() {
+                    Some(dash) if dash.1 == position => dash.1 = next,
+                    _ => dashes.push((position, next)),
+                }
+            }
+            position = next;
+        }
+
+        dashes
+    }
+}
+
+type Point = (f64, f64);
+
+fn add(a: Point, b: Point) -> Point {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn scale(a: Point, factor: f64) -> Point {
+    (a.0 * factor, a.1 * factor)
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn length(a: Point) -> f64 {
+    a.0.hypot(a.1)
+}
+
+/// Rotates by a quarter turn
+fn perpendicular(a: Point) -> Point {
+    (-a.1, a.0)
+}
+
+/// Adds the contour with a positive orientation,
+/// so overlapping contours are merged by the non-zero fill rule
+fn push_oriented(edges: &mut Vec<Edge>, mut points: Vec<Point>) {
+    let area: f64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    if area < 0.0 {
+        points.reverse();
+    }
+
+    push_contour(edges, &points);
+}
+
+fn push_circle(edges: &mut Vec<Edge>, center: Point, radius: f64) {
+    let segments = (TAU * radius / 2.0).ceil().max(8.0) as usize;
+    let points = (0..segments)
+        .map(|i| {
+            let (sin, cos) = (TAU * i as f64 / segments as f64).sin_cos();
+            add(center, (cos * radius, sin * radius))
+        })
+        .collect();
+
+    push_oriented(edges, points);
+}
+
+/// A line through points with a width, caps, joins and a dash pattern.
+///
+/// The points are in pixel space, where `(0.5, 0.5)` is the center of the top-left pixel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroked {
+    pub points: Vec<(f64, f64)>,
+    pub closed: bool,
+    pub stroke: Stroke,
+}
+
+impl Stroked {
+    pub fn new(points: Vec<(f64, f64)>, closed: bool, stroke: Stroke) -> Self {
+        Self {
+            points,
+            closed,
+            stroke,
+        }
+    }
+
+    /// Strokes the line through the centers of the given pixels
+    pub fn through_pixels(pixels: &[Pixel], closed: bool, stroke: Stroke) -> Self {
+        let points = pixels
+            .iter()
+            .map(|p| (p.x() as f64 + 0.5, p.y() as f64 + 0.5))
+            .collect();
+
+        Self::new(points, closed, stroke)
+    }
+
+    fn push_join(&self, edges: &mut Vec<Edge>, vertex: Point, incoming: Point, outgoing: Point) {
+        let half = self.stroke.width as f64 / 2.0;
+        let turn = incoming.0 * outgoing.1 - incoming.1 * outgoing.0;
+        if turn.abs() < 1e-9 && dot(incoming, outgoing) > 0.0 {
+            return;
+        }
+
+        if self.stroke.join == LineJoin::Round {
+            push_circle(edges, vertex, half);
+            return;
+        }
+
+        // the offsets on the outer side of the turn
+        let mut first = scale(perpendicular(incoming), half);
+        if dot(first, outgoing) > 0.0 {
+            first = scale(first, -1.0);
+        }
+        let side = if dot(first, perpendicular(incoming)) > 0.0 {
+            1.0
+        } else {
+            -1.0
+        };
+        let second = scale(perpendicular(outgoing), half * side);
+
+        let bevel = vec![vertex, add(vertex, first), add(vertex, second)];
+        let middle = add(first, second);
+        if self.stroke.join == LineJoin::Bevel || length(middle) < 1e-9 {
+            push_oriented(edges, bevel);
+            return;
+        }
+
+        let direction = scale(middle, 1.0 / length(middle));
+        let miter = half * half / dot(direction, first);
+        if miter > MITER_LIMIT * half * 2.0 {
+            push_oriented(edges, bevel);
+        } else {
+            let tip = add(vertex, scale(direction, miter));
+            push_oriented(
+                edges,
+                vec![vertex, add(vertex, first), tip, add(vertex, second)],
+            );
+        }
+    }
+
+    fn push_piece(
+        &self,
+        edges: &mut Vec<Edge>,
+        mut from: Point,
+        mut to: Point,
+        caps: (bool, bool),
+    ) {
+        let half = self.stroke.width as f64 / 2.0;
+        let segment = sub(to, from);
+        let direction = scale(segment, 1.0 / length(segment));
+
+        match self.stroke.cap {
+            LineCap::Butt => (),
+            LineCap::Square => {
+                if caps.0 {
+                    from = sub(from, scale(direction, half));
+                }
+                if caps.1 {
+                    to = add(to, scale(direction, half));
+                }
+            }
+            LineCap::Round => {
+                if caps.0 {
+                    push_circle(edges, from, half);
+                }
+                if caps.1 {
+                    push_circle(edges, to, half);
+                }
+            }
+        }
+
+        let offset = scale(perpendicular(direction), half);
+        push_oriented(
+            edges,
+            vec![
+                add(from, offset),
+                add(to, offset),
+                sub(to, offset),
+                sub(from, offset),
+            ],
+        );
+    }
+
+    /// The outlines of all pieces, caps and joins, which are filled with the non-zero rule
+    pub(crate) fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        let half = self.stroke.width as f64 / 2.0;
+        if half <= 0.0 {
+            return edges;
+        }
+
+        let mut points = self.points.clone();
+        points.dedup();
+        if self.closed && points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        if points.is_empty() {
+            return edges;
+        }
+
+        // a single point has no direction, it is drawn as a dot of the stroke width:
+        // open strokes in the shape of their caps, closed ones in the shape of their joins
+        if points.len() == 1 {
+            let point = points[0];
+            let square = vec![
+                add(point, (-half, -half)),
+                add(point, (half, -half)),
+                add(point, (half, half)),
+                add(point, (-half, half)),
+            ];
+            match (self.closed, self.stroke.cap, self.stroke.join) {
+                (false, LineCap::Butt, _) => (),
+                (false, LineCap::Square, _) => push_oriented(&mut edges, square),
+                (false, LineCap::Round, _) | (true, _, LineJoin::Round) => {
+                    push_circle(&mut edges, point, half)
+                }
+                (true, _, _) => push_oriented(&mut edges, square),
+            }
+            return edges;
+        }
+
+        let count = points.len();
+        let segments = if self.closed { count } else { count - 1 };
+        let mut distance = 0.0;
+        for i in 0..segments {
+            let from = points[i];
+            let to = points[(i + 1) % count];
+            let segment = sub(to, from);
+            let segment_length = length(segment);
+            let direction = scale(segment, 1.0 / segment_length);
+
+            // ends at vertices are covered by joins, only open ends get caps
+            let first_open = !self.closed && i == 0;
+            let last_open = !self.closed && i == segments - 1;
+            for (start, end) in self.stroke.dashes(distance, distance + segment_length) {
+                let caps = (
+                    start > distance || first_open,
+                    end < distance + segment_length || last_open,
+                );
+                self.push_piece(
+                    &mut edges,
+                    add(from, scale(direction, start - distance)),
+                    add(from, scale(direction, end - distance)),
+                    caps,
+                );
+            }
+            distance += segment_length;
+
+            if (self.closed || i + 1 < segments) && self.stroke.is_on(distance) {
+                let next = sub(points[(i + 2) % count], to);
+                let outgoing = scale(next, 1.0 / length(next));
+                self.push_join(&mut edges, to, direction, outgoing);
+            }
+        }
+
+        edges
+    }
+}
+
+pub type StrokedIter = SpanPixels<PolygonSpans>;
+
+impl Drawable for Stroked {
+    type IntoIter = StrokedIter;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for Stroked {
+    type Spans = PolygonSpans;
+
+    fn spans(&self) -> Self::Spans {
+        PolygonSpans::new(self.edges(), FillRule::NonZero)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::{Line, Polyline, Span};
+    use std::collections::HashSet;
+
+    #[test]
+    fn thick_horizontal_line() {
+        // Arrange
+        let line = Line::new((2, 5).into(), (10, 5).into());
+
+        // Act
+        let butt: Vec<Span> = line.stroked(Stroke::new(3.0)).spans().collect();
+        let square: Vec<Span> = line
+            .stroked(Stroke::new(3.0).with_cap(LineCap::Square))
+            .spans()
+            .collect();
+
+        // Assert
+        assert_eq!(
+            butt,
+            vec![
+                Span::new(4, 2, 10),
+                Span::new(5, 2, 10),
+                Span::new(6, 2, 10)
+            ]
+        );
+        assert_eq!(square[1], Span::new(5, 1, 12));
+    }
+
+    #[test]
+    fn dashes_follow_the_pattern() {
+        // Arrange
+        let line = Line::new((0, 0).into(), (16, 0).into());
+        let stroke = Stroke::new(1.0).with_pattern(0xf0f0_f0f0);
+
+        // Act
+        let spans: Vec<Span> = line.stroked(stroke).spans().collect();
+
+        // Assert
+        assert_eq!(spans, vec![Span::new(0, 0, 4), Span::new(0, 8, 12)]);
+    }
+
+    #[test]
+    fn joins_do_not_overlap() {
+        for &join in &[LineJoin::Miter, LineJoin::Bevel, LineJoin::Round] {
+            // Arrange
+            let corner = Stroked::through_pixels(
+                &[(5, 5).into(), (25, 5).into(), (25, 25).into()],
+                false,
+                Stroke::new(5.0).with_join(join),
+            );
+
+            // Act
+            let pixels: Vec<Pixel> = corner.pixels().collect();
+            let unique: HashSet<Pixel> = pixels.iter().copied().collect();
+
+            // Assert
+            assert_eq!(pixels.len(), unique.len());
+            assert_eq!(unique.contains(&(27, 3).into()), join == LineJoin::Miter);
+        }
+    }
+
+    #[test]
+    fn empty_points() {
+        // Arrange
+        let open = Stroked::new(vec![], false, Stroke::new(2.0));
+        let closed = Stroked::new(vec![], true, Stroke::new(2.0));
+
+        // Act
+        let polyline = Polyline::new(vec![]).stroked(Stroke::new(2.0));
+
+        // Assert
+        assert_eq!(open.pixels().count(), 0);
+        assert_eq!(closed.pixels().count(), 0);
+        assert_eq!(polyline.pixels().count(), 0);
+    }
+
+    #[test]
+    fn single_points() {
+        // Arrange
+        let stroke = Stroke::new(4.0);
+        let point = vec![(10.0, 10.0)];
+        let repeated = vec![(10.0, 10.0), (10.0, 10.0), (10.0, 10.0)];
+
+        // Act
+        let open = Stroked::new(point.clone(), false, stroke);
+        let closed = Stroked::new(point, true, stroke);
+        let closed_repeated = Stroked::new(repeated, true, stroke);
+        let round = Stroked::new(vec![(10.0, 10.0)], true, stroke.with_join(LineJoin::Round));
+
+        // Assert
+        let spans = |stroked: &Stroked| stroked.spans().collect::<Vec<Span>>();
+        assert!(spans(&open).is_empty());
+        assert_eq!(
+            spans(&closed),
+            (8..12).map(|y| Span::new(y, 8, 12)).collect::<Vec<_>>()
+        );
+        assert_eq!(spans(&closed_repeated), spans(&closed));
+        assert!(round.pixels().any(|p| p == (10, 10).into()));
+    }
+}
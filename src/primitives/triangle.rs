@@ -0,0 +1,99 @@
+use crate::{
+    draw::{Drawable, SpanDrawable},
+    pixel::Pixel,
+};
+
+use super::{
+    bounds::HitTest,
+    polygon::{
+        corners_bounding_box, corners_contain, pixel_corners, FillRule, OutlineIter, PolygonSpans,
+    },
+    rectangle::Rectangle,
+    span::SpanPixels,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: Pixel,
+    pub b: Pixel,
+    pub c: Pixel,
+}
+
+impl Triangle {
+    pub fn new(a: Pixel, b: Pixel, c: Pixel) -> Self {
+        Self { a, b, c }
+    }
+
+    fn vertices(&self) -> [Pixel; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    pub fn hollow(self) -> HollowTriangle {
+        HollowTriangle(self)
+    }
+
+    pub fn filled(self) -> FilledTriangle {
+        FilledTriangle(self)
+    }
+}
+
+impl HitTest for Triangle {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        corners_bounding_box(&self.vertices())
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        corners_contain(&self.vertices(), FillRule::NonZero, pixel)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HollowTriangle(pub Triangle);
+impl Drawable for HollowTriangle {
+    type IntoIter = OutlineIter<[Pixel; 3]>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        OutlineIter::new(self.0.vertices(), true)
+    }
+}
+
+/// Filled following the top-left rule,
+/// so triangles sharing an edge do not draw it twice
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilledTriangle(pub Triangle);
+impl Drawable for FilledTriangle {
+    type IntoIter = SpanPixels<PolygonSpans>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        SpanPixels::new(self.spans())
+    }
+}
+
+impl SpanDrawable for FilledTriangle {
+    type Spans = PolygonSpans;
+
+    fn spans(&self) -> Self::Spans {
+        PolygonSpans::from_points(&pixel_corners(&self.0.vertices()), FillRule::NonZero)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn shared_edge_is_drawn_once() {
+        // Arrange, a quad split along its diagonal
+        let first = Triangle::new((0, 0).into(), (10, 0).into(), (10, 7).into()).filled();
+        let second = Triangle::new((0, 0).into(), (10, 7).into(), (0, 7).into()).filled();
+
+        // Act
+        let first: HashSet<Pixel> = first.pixels().collect();
+        let second: HashSet<Pixel> = second.pixels().collect();
+
+        // Assert
+        assert_eq!(first.intersection(&second).count(), 0);
+        assert_eq!(first.len() + second.len(), 70);
+    }
+}
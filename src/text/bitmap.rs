@@ -0,0 +1,458 @@
+use std::{collections::HashMap, io, sync::Arc};
+
+use crate::image::Image;
+
+use super::cache::Bitmap;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BitmapGlyph {
+    /// The top left pixel is relative to the caret on the baseline
+    pub(crate) bitmap: Arc<Bitmap>,
+    pub(crate) advance: u32,
+}
+
+/// A font of fixed pixel glyphs for crisp pixel art text.
+/// Drawn at a height, glyphs are scaled by the largest whole number fitting into it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitmapFont {
+    ascent: u32,
+    descent: u32,
+    glyphs: HashMap<char, BitmapGlyph>,
+}
+
+impl BitmapFont {
+    fn new(ascent: u32, descent: u32) -> Self {
+        Self {
+            ascent,
+            descent,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Adds a glyph of `width` by `height` pixels, whose top left is at `min` from the caret
+    fn insert(
+        &mut self,
+        c: char,
+        (width, height): (usize, usize),
+        min: (i32, i32),
+        advance: u32,
+        ink: impl Fn(usize, usize) -> bool,
+    ) {
+        let coverage = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| if ink(x, y) { 1.0 } else { 0.0 })
+            .collect();
+
+        let bitmap = Bitmap {
+            min,
+            width,
+            coverage,
+        };
+
+        self.glyphs.insert(
+            c,
+            BitmapGlyph {
+                bitmap: Arc::new(bitmap),
+                advance,
+            },
+        );
+    }
+
+    /// The height of a line at the native size of the font
+    pub fn line_height(&self) -> u32 {
+        self.ascent + self.descent
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// The whole number the glyphs are scaled by, when drawn at the given height
+    pub fn scale_for(&self, height: u32) -> u32 {
+        (height / self.line_height().max(1)).max(1)
+    }
+
+    pub(crate) fn glyph(&self, c: char) -> Option<&BitmapGlyph> {
+        self.glyphs.get(&c)
+    }
+
+    pub(crate) fn ascent(&self) -> u32 {
+        self.ascent
+    }
+
+    pub(crate) fn descent(&self) -> u32 {
+        self.descent
+    }
+
+    /// Cuts a sprite sheet into glyphs of `cell_width` by `cell_height` pixels.
+    /// The cells hold consecutive characters starting with `first`, row by row.
+    ///
+    /// Pixels at least half opaque are drawn, the others are background.
+    /// Returns None, if a cell size is zero.
+    pub fn from_sprite_sheet(
+        sheet: &Image,
+        cell_width: u32,
+        cell_height: u32,
+        first: char,
+    ) -> Option<Self> {
+        if cell_width == 0 || cell_height == 0 {
+            return None;
+        }
+
+        let (sheet_width, sheet_height, cell_width, cell_height) = (
+            sheet.width() as usize,
+            sheet.height() as usize,
+            cell_width as usize,
+            cell_height as usize,
+        );
+        let colors = sheet.colors();
+
+        let columns = sheet_width / cell_width;
+        let rows = sheet_height / cell_height;
+
+        let mut font = BitmapFont::new(cell_height as u32, 0);
+        for cell in 0..columns * rows {
+            let c = match std::char::from_u32(first as u32 + cell as u32) {
+                Some(c) => c,
+                None => continue,
+            };
+            let left = (cell % columns) * cell_width;
+            let top = (cell / columns) * cell_height;
+
+            font.insert(
+                c,
+                (cell_width, cell_height),
+                (0, -(cell_height as i32)),
+                cell_width as u32,
+                |x, y| colors[(top + y) * sheet_width + left + x].a() >= 128,
+            );
+        }
+
+        Some(font)
+    }
+
+    /// Parses a PC Screen Font of version 1 or 2, like the Linux console fonts.
+    /// Without a unicode table, glyph `n` is used for the character `n`.
+    pub fn from_psf(data: &[u8]) -> io::Result<Self> {
+        let u32_at = |offset: usize| -> io::Result<usize> {
+            match data.get(offset..offset + 4) {
+                Some(b) => Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize),
+                None => Err(invalid("truncated header")),
+            }
+        };
+
+        // glyph count, width, height, header size and whether the unicode table is UTF-8
+        let (count, width, height, header, utf8, has_table) = match data {
+            [0x36, 0x04, mode, size, ..] => {
+                let count = if mode & 0x01 != 0 { 512 } else { 256 };
+                (count, 8, *size as usize, 4, false, mode & 0x06 != 0)
+            }
+            [0x72, 0xb5, 0x4a, 0x86, ..] => (
+                u32_at(16)?,
+                u32_at(28)?,
+                u32_at(24)?,
+                u32_at(8)?,
+                true,
+                u32_at(12)? & 0x01 != 0,
+            ),
+            _ => return Err(invalid("not a PC Screen Font")),
+        };
+
+        // rows are padded to whole bytes
+        let row_bytes = width / 8 + (width % 8 != 0) as usize;
+        // the sizes come from the file, so they may overflow
+        let glyph_bytes = row_bytes.checked_mul(height);
+        let table = glyph_bytes
+            .and_then(|glyph_bytes| count.checked_mul(glyph_bytes))
+            .and_then(|glyphs| glyphs.checked_add(header));
+        let (glyph_bytes, table) = match (glyph_bytes, table) {
+            (Some(glyph_bytes), Some(table)) if width != 0 && data.len() >= table => {
+                (glyph_bytes, table)
+            }
+            _ => return Err(invalid("truncated glyphs")),
+        };
+
+        let mut font = BitmapFont::new(height as u32, 0);
+        let insert = |font: &mut BitmapFont, c: char, index: usize| {
+            let glyph = &data[header + index * glyph_bytes..header + (index + 1) * glyph_bytes];
+            font.insert(
+                c,
+                (width, height),
+                (0, -(height as i32)),
+                width as u32,
+                |x, y| glyph[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0,
+            );
+        };
+
+        if !has_table {
+            for index in 0..count {
+                if let Some(c) = std::char::from_u32(index as u32) {
+                    insert(&mut font, c, index);
+                }
+            }
+            return Ok(font);
+        }
+
+        // each glyph lists its characters, followed by sequences, which are ignored
+        let mut entries = data[table..].iter().copied();
+        for index in 0..count {
+            if utf8 {
+                let entry: Vec<u8> = entries.by_ref().take_while(|b| *b != 0xff).collect();
+                let single = entry.split(|b| *b == 0xfe).next().unwrap_or(&[]);
+                let text = std::str::from_utf8(single)
+                    .map_err(|_| invalid(format!("glyph {}: invalid unicode table", index)))?;
+                for c in text.chars() {
+                    insert(&mut font, c, index);
+                }
+            } else {
+                let mut in_sequence = false;
+                loop {
+                    let value = match (entries.next(), entries.next()) {
+                        (Some(low), Some(high)) => u16::from_le_bytes([low, high]),
+                        _ => return Err(invalid("truncated unicode table")),
+                    };
+                    match value {
+                        0xffff => break,
+                        0xfffe => in_sequence = true,
+                        _ if in_sequence => (),
+                        _ => {
+                            if let Some(c) = std::char::from_u32(value as u32) {
+                                insert(&mut font, c, index);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(font)
+    }
+
+    /// Parses a font in the Glyph Bitmap Distribution Format of X11
+    pub fn from_bdf(source: &str) -> io::Result<Self> {
+        let mut ascent = None;
+        let mut descent = None;
+        let mut bounding_box = None;
+        let mut font = BitmapFont::new(0, 0);
+
+        let mut encoding = None;
+        let mut advance = None;
+        let mut bbx = None;
+        let mut rows: Vec<Vec<u8>> = Vec::new();
+        let mut in_bitmap = false;
+
+        for (number, line) in source.lines().enumerate() {
+            let error = |message: &str| invalid(format!("line {}: {}", number + 1, message));
+            let mut tokens = line.split_whitespace();
+            let keyword = match tokens.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+            let numbers = || -> io::Result<Vec<i32>> {
+                line.split_whitespace()
+                    .skip(1)
+                    .map(|token| token.parse().map_err(|_| error("invalid number")))
+                    .collect()
+            };
+
+            if in_bitmap && keyword != "ENDCHAR" {
+                // two hex digits per byte
+                if !keyword.is_ascii() || keyword.len() % 2 != 0 {
+                    return Err(error("invalid bitmap row"));
+                }
+                let row = (0..keyword.len() / 2)
+                    .map(|i| u8::from_str_radix(&keyword[i * 2..i * 2 + 2], 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map_err(|_| error("invalid bitmap row"))?;
+                rows.push(row);
+                continue;
+            }
+
+            match keyword {
+                "FONTBOUNDINGBOX" => bounding_box = Some(numbers()?),
+                "FONT_ASCENT" => ascent = numbers()?.first().copied(),
+                "FONT_DESCENT" => descent = numbers()?.first().copied(),
+                "STARTCHAR" => {
+                    encoding = None;
+                    advance = None;
+                    bbx = None;
+                    rows.clear();
+                }
+                "ENCODING" => encoding = numbers()?.first().copied(),
+                "DWIDTH" => advance = numbers()?.first().copied(),
+                "BBX" => match numbers()?.as_slice() {
+                    &[width, height, x, y] if width >= 0 && height >= 0 => {
+                        bbx = Some((width, height, x, y))
+                    }
+                    _ => return Err(error("expected width, height and offset")),
+                },
+                "BITMAP" => in_bitmap = true,
+                "ENDCHAR" => {
+                    in_bitmap = false;
+                    let (width, height, x, y) = bbx.ok_or_else(|| error("glyph without BBX"))?;
+                    if rows.len() < height as usize {
+                        return Err(error("glyph with missing bitmap rows"));
+                    }
+
+                    // the size comes from the file, the rows bound the bitmap allocated for it
+                    let longest = rows[..height as usize].iter().map(Vec::len).max();
+                    if width as usize > longest.unwrap_or(0) * 8 {
+                        return Err(error("glyph wider than its bitmap rows"));
+                    }
+                    let top = y
+                        .checked_add(height)
+                        .and_then(i32::checked_neg)
+                        .ok_or_else(|| error("glyph offset out of range"))?;
+
+                    // unencoded glyphs have a negative encoding
+                    let c = match encoding {
+                        Some(encoding) if encoding >= 0 => std::char::from_u32(encoding as u32),
+                        _ => None,
+                    };
+                    let c = match c {
+                        Some(c) => c,
+                        None => continue,
+                    };
+
+                    let rows = &rows;
+                    font.insert(
+                        c,
+                        (width as usize, height as usize),
+                        (x, top),
+                        advance.unwrap_or(width).max(0) as u32,
+                        |x, y| match rows[y].get(x / 8) {
+                            Some(byte) => byte & (0x80 >> (x % 8)) != 0,
+                            None => false,
+                        },
+                    );
+                }
+                _ => (),
+            }
+        }
+
+        // without the properties, the bounding box of all glyphs gives the line
+        let (ascent, descent) = match (ascent, descent, bounding_box.as_deref()) {
+            (Some(ascent), Some(descent), _) => (ascent, descent),
+            (_, _, Some(&[_, height, _, y])) => (height + y, -y),
+            _ => {
+                return Err(invalid(
+                    "missing FONT_ASCENT, FONT_DESCENT and FONTBOUNDINGBOX",
+                ))
+            }
+        };
+
+        font.ascent = ascent.max(0) as u32;
+        font.descent = descent.max(0) as u32;
+        Ok(font)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::{color::Color, draw::Drawable, pixel::Pixel, text::Text};
+
+    /// A font with a single glyph for 'A' of 6 pixels
+    pub(crate) const BDF: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 1
+STARTCHAR A
+ENCODING 65
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+40
+A0
+E0
+ENDCHAR
+ENDFONT
+";
+
+    fn pixels(font: BitmapFont, content: &str, height: u32) -> Vec<Pixel> {
+        let mut pixels: Vec<Pixel> = Text::new((0, 0).into(), content, height)
+            .with_font(font.into())
+            .pixels()
+            .collect();
+        pixels.sort_by_key(|p| (p.y(), p.x()));
+        pixels
+    }
+
+    #[test]
+    fn parses_bdf() {
+        // Arrange
+        let font = BitmapFont::from_bdf(BDF).unwrap();
+
+        // Act
+        let a = pixels(font.clone(), "A", 4);
+
+        // Assert
+        assert_eq!(font.line_height(), 4);
+        assert_eq!(a.len(), 6);
+        assert_eq!(a[0], Pixel((1, 0)));
+        assert_eq!(a[5], Pixel((2, 2)));
+        assert!(BitmapFont::from_bdf("STARTFONT 2.1\nBBX 1 x 0 0\n").is_err());
+        for row in &["0é", "A"] {
+            let source = BDF.replacen("A0\n", &format!("{}\n", row), 1);
+            assert!(BitmapFont::from_bdf(&source).is_err());
+        }
+        for bbx in &[
+            "BBX 2000000000 1 0 0",
+            "BBX 9 3 0 0",
+            "BBX 3 3 0 2147483647",
+        ] {
+            let source = BDF.replacen("BBX 3 3 0 0", bbx, 1);
+            assert!(BitmapFont::from_bdf(&source).is_err());
+        }
+    }
+
+    #[test]
+    fn parses_psf() {
+        // Arrange, version 1 with a unicode table mapping glyph 1 to 'x'
+        let mut data = vec![0x36, 0x04, 0x02, 2];
+        data.extend((0..256).flat_map(|i| if i == 1 { [0x80, 0x01] } else { [0, 0] }));
+        for i in 0..256 {
+            if i == 1 {
+                data.extend(&[b'x', 0]);
+            }
+            data.extend(&[0xff, 0xff]);
+        }
+
+        // Act
+        let font = BitmapFont::from_psf(&data).unwrap();
+
+        // Assert
+        assert!(font.has_glyph('x'));
+        assert!(!font.has_glyph('a'));
+        assert_eq!(pixels(font, "x", 2), vec![Pixel((0, 0)), Pixel((7, 1))]);
+        assert!(BitmapFont::from_psf(&data[..100]).is_err());
+
+        let mut huge = vec![0x72, 0xb5, 0x4a, 0x86, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0];
+        huge.extend(&[0xff; 16]);
+        assert!(BitmapFont::from_psf(&huge).is_err());
+    }
+
+    #[test]
+    fn sprite_sheet_scales_by_whole_numbers() {
+        // Arrange, two 2x2 cells, a diagonal and a full block
+        let (o, x) = (Color::TRANSPARENT, Color::WHITE);
+        let sheet = Image::new(4, 2, vec![x, o, x, x, o, x, x, x]).unwrap();
+
+        // Act
+        let font = BitmapFont::from_sprite_sheet(&sheet, 2, 2, 'a').unwrap();
+
+        // Assert
+        assert_eq!(font.scale_for(5), 2);
+        assert_eq!(pixels(font.clone(), "a", 2).len(), 2);
+        assert_eq!(pixels(font.clone(), "ab", 5).len(), 8 + 16);
+        assert_eq!(pixels(font, "b", 6)[0], Pixel((0, 0)));
+        assert!(BitmapFont::from_sprite_sheet(&sheet, 0, 2, 'a').is_none());
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use rusttype::{point, PositionedGlyph};
+
+/// Glyphs are rasterized at quarter pixel offsets, so each glyph is cached at most 16 times
+const SUBPIXEL_STEPS: f32 = 4.0;
+
+/// The default memory cap of one megabyte
+const DEFAULT_CAPACITY: usize = 1 << 20;
+
+/// The coverage of a rasterized glyph
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Bitmap {
+    /// The top left pixel of the glyph where it was rasterized
+    pub(crate) min: (i32, i32),
+    pub(crate) width: usize,
+    pub(crate) coverage: Vec<f32>,
+}
+
+impl Bitmap {
+    /// Rasterizes the glyph, reusing the memory of the bitmap.
+    /// Glyphs without an outline, e.g. spaces, result in an empty bitmap.
+    pub(crate) fn rasterize(&mut self, glyph: &PositionedGlyph<'static>) {
+        self.coverage.clear();
+        let bb = match glyph.pixel_bounding_box() {
+            Some(bb) => bb,
+            None => {
+                self.width = 0;
+                return;
+            }
+        };
+
+        self.min = (bb.min.x, bb.min.y);
+        self.width = bb.width() as usize;
+        self.coverage
+            .resize(bb.width() as usize * bb.height() as usize, 0.0);
+
+        let coverage = &mut self.coverage;
+        let width = self.width;
+        glyph.draw(|x, y, z| {
+            // rasterizing can overshoot slightly
+            coverage[y as usize * width + x as usize] = z.min(1.0);
+        });
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        std::mem::size_of::<Bitmap>() + self.coverage.len() * std::mem::size_of::<f32>()
+    }
+}
+
+/// A glyph of a font at a size and sub pixel offset
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    face: usize,
+    height: u32,
+    glyph: u16,
+    offset: (u8, u8),
+}
+
+/// How well the cache is doing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Glyphs reused from the cache
+    pub hits: u64,
+    /// Glyphs, which had to be rasterized
+    pub misses: u64,
+    /// Glyphs dropped to stay below the memory cap
+    pub evictions: u64,
+    /// Glyphs currently cached
+    pub glyphs: usize,
+    /// Memory currently used by the cached glyphs
+    pub bytes: usize,
+}
+
+struct Entry {
+    bitmap: Arc<Bitmap>,
+    last_used: u64,
+}
+
+struct Cache {
+    capacity: usize,
+    entries: HashMap<GlyphKey, Entry>,
+    /// The glyphs by the tick they were last used at, the least recently used first
+    recency: BTreeMap<u64, GlyphKey>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl Cache {
+    /// Drops the least recently used glyphs, until the cache fits into its capacity
+    fn evict(&mut self) {
+        while self.stats.bytes > self.capacity {
+            let (tick, oldest) = match self.recency.iter().next() {
+                Some((tick, key)) => (*tick, *key),
+                None => break,
+            };
+
+            self.recency.remove(&tick);
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.stats.bytes -= entry.bitmap.size_in_bytes();
+                self.stats.glyphs -= 1;
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// Keeps rasterized glyphs across frames, so drawn text only has to be laid out.
+/// Glyphs are placed at quarter pixel precision, which is not noticeable at usual sizes.
+///
+/// Cloning is cheap, all clones share the same glyphs.
+/// When the cached glyphs exceed the memory cap, the least recently used ones are dropped.
+#[derive(Clone)]
+pub struct GlyphCache(Arc<Mutex<Cache>>);
+
+impl GlyphCache {
+    /// `capacity` is the memory cap in bytes
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(Cache {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            stats: CacheStats::default(),
+        })))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cache> {
+        // the cache stays consistent, even if a thread panicked while holding it
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Changes the memory cap, dropping glyphs if they exceed it
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut cache = self.lock();
+        cache.capacity = capacity;
+        cache.evict();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drops all glyphs, the counters of the statistics are kept
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.entries.clear();
+        cache.recency.clear();
+        cache.stats.glyphs = 0;
+        cache.stats.bytes = 0;
+    }
+
+    /// The coverage of the glyph and its top left pixel,
+    /// rasterized only if it is not cached yet
+    pub(crate) fn get(
+        &self,
+        face: usize,
+        height: u32,
+        glyph: &PositionedGlyph<'static>,
+    ) -> ((i32, i32), Arc<Bitmap>) {
+        let position = glyph.position();
+        let snap = |v: f32| {
+            let steps = (v * SUBPIXEL_STEPS).round();
+            let whole = (steps / SUBPIXEL_STEPS).floor();
+            (whole as i32, (steps - whole * SUBPIXEL_STEPS) as u8)
+        };
+        let (x, offset_x) = snap(position.x);
+        let (y, offset_y) = snap(position.y);
+
+        let key = GlyphKey {
+            face,
+            height,
+            glyph: glyph.id().0,
+            offset: (offset_x, offset_y),
+        };
+
+        let mut guard = self.lock();
+        let cache = &mut *guard;
+        cache.tick += 1;
+        let tick = cache.tick;
+
+        let bitmap = match cache.entries.get_mut(&key) {
+            Some(entry) => {
+                cache.recency.remove(&entry.last_used);
+                cache.recency.insert(tick, key);
+                entry.last_used = tick;
+                cache.stats.hits += 1;
+                entry.bitmap.clone()
+            }
+            None => {
+                let mut bitmap = Bitmap::default();
+                bitmap.rasterize(&glyph.unpositioned().clone().positioned(point(
+                    offset_x as f32 / SUBPIXEL_STEPS,
+                    offset_y as f32 / SUBPIXEL_STEPS,
+                )));
+                let bitmap = Arc::new(bitmap);
+
+                cache.stats.misses += 1;
+                cache.stats.glyphs += 1;
+                cache.stats.bytes += bitmap.size_in_bytes();
+                cache.recency.insert(tick, key);
+                cache.entries.insert(
+                    key,
+                    Entry {
+                        bitmap: bitmap.clone(),
+                        last_used: tick,
+                    },
+                );
+                cache.evict();
+
+                bitmap
+            }
+        };
+
+        ((x + bitmap.min.0, y + bitmap.min.1), bitmap)
+    }
+}
+
+impl Default for GlyphCache {
+    /// Caps the memory at one megabyte
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{draw::Drawable, pixel::Pixel, text::Text};
+
+    fn draw(cache: &GlyphCache) -> Vec<Pixel> {
+        Text::new((5, 5).into(), "Hello", 16)
+            .with_cache(cache.clone())
+            .pixels()
+            .collect()
+    }
+
+    #[test]
+    fn reuses_glyphs() {
+        // Arrange
+        let cache = GlyphCache::default();
+
+        // Act
+        let first = draw(&cache);
+        let after_first = cache.stats();
+        let second = draw(&cache);
+        let after_second = cache.stats();
+
+        // Assert
+        assert_eq!(first, second);
+        assert_eq!(after_first.hits + after_first.misses, 5);
+        assert_eq!(after_second.misses, after_first.misses);
+        assert_eq!(after_second.hits, after_first.hits + 5);
+        assert!(after_second.bytes > 0);
+    }
+
+    #[test]
+    fn stays_below_capacity() {
+        // Arrange
+        let cache = GlyphCache::default();
+        draw(&cache);
+
+        // Act
+        cache.set_capacity(0);
+        let pixels = draw(&cache);
+
+        // Assert
+        let stats = cache.stats();
+        assert!(!pixels.is_empty());
+        assert_eq!(stats.glyphs, 0);
+        assert_eq!(stats.bytes, 0);
+        assert_eq!(stats.evictions, stats.misses);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        // Arrange
+        let cache = GlyphCache::new(usize::MAX);
+        let draw = |content| {
+            Text::new((5, 5).into(), content, 16)
+                .with_cache(cache.clone())
+                .pixels()
+                .count()
+        };
+        draw("l");
+        draw("b");
+        cache.set_capacity(cache.stats().bytes);
+
+        // Act
+        draw("l");
+        draw("i");
+        let before = cache.stats();
+        draw("l");
+        draw("b");
+
+        // Assert
+        let after = cache.stats();
+        assert_eq!(before.evictions, 1);
+        assert_eq!(after.hits, before.hits + 1);
+        assert_eq!(after.misses, before.misses + 1);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{
+    color::Color,
+    draw::{ColoredDrawable, Drawable},
+    mask::Mask,
+    pixel::Pixel,
+    primitives::{bounding_box, FilledRectangle, FilledRectangleIter, HitTest},
+};
+
+use super::Text;
+
+/// Makes text readable over busy backgrounds.
+/// The effects are drawn below the text, the background first, then the shadow and the outline.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    /// The color and width in pixels of the line around the glyphs
+    pub outline: Option<(Color, u32)>,
+    /// The color and offset of a copy of the outlined glyphs behind them
+    pub shadow: Option<(Color, (i32, i32))>,
+    /// The color and padding of a box around everything drawn
+    pub background: Option<(Color, u32)>,
+}
+
+impl TextEffects {
+    /// No effects at all
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_outline(mut self, color: impl Into<Color>, width: u32) -> Self {
+        self.outline = Some((color.into(), width));
+        self
+    }
+
+    pub fn with_shadow(mut self, color: impl Into<Color>, offset: (i32, i32)) -> Self {
+        self.shadow = Some((color.into(), offset));
+        self
+    }
+
+    pub fn with_background(mut self, color: impl Into<Color>, padding: u32) -> Self {
+        self.background = Some((color.into(), padding));
+        self
+    }
+}
+
+/// Text in a color with its effects, computed from the pixels of its glyphs
+pub struct DecoratedText<'a> {
+    pub text: Text<'a>,
+    pub color: Color,
+}
+
+impl<'a> ColoredDrawable for DecoratedText<'a> {
+    type Colored = DecoratedTextIter;
+
+    fn colored_pixels(&self) -> Self::Colored {
+        let empty = DecoratedTextIter {
+            background: None,
+            layers: None,
+        };
+        let bb = match self.text.bounding_box() {
+            Some(bb) => bb,
+            None => return empty,
+        };
+
+        // the masks cover the glyphs and their outline, their origin is at `(left, top)`
+        let outline_width = match self.text.effects.outline {
+            Some((_, width)) => width,
+            None => 0,
+        };
+        let (left, top) = (
+            bb.top_left.x() as i64 - outline_width as i64,
+            bb.top_left.y() as i64 - outline_width as i64,
+        );
+        let mut glyphs = Mask::new(bb.width + 2 * outline_width, bb.height + 2 * outline_width);
+
+        let (mut right, mut bottom) = (i64::MIN, i64::MIN);
+        let (mut glyph_left, mut glyph_top) = (i64::MAX, i64::MAX);
+        for p in self.text.pixels() {
+            let (x, y) = (p.x() as i64, p.y() as i64);
+            glyphs.insert(Pixel(((x - left) as u32, (y - top) as u32)));
+            glyph_left = glyph_left.min(x);
+            glyph_top = glyph_top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+        if glyphs.is_empty() {
+            return empty;
+        }
+
+        let outlined = self
+            .text
+            .effects
+            .outline
+            .map(|(_, width)| glyphs.dilate(width));
+
+        // the outlined glyphs and their shadow, without clipping them to the buffer
+        let width = outline_width as i64;
+        let (mut bounds_left, mut bounds_top, mut bounds_right, mut bounds_bottom) = (
+            glyph_left - width,
+            glyph_top - width,
+            right + width,
+            bottom + width,
+        );
+        if let Some((_, (dx, dy))) = self.text.effects.shadow {
+            bounds_left = bounds_left.min(bounds_left + dx as i64);
+            bounds_top = bounds_top.min(bounds_top + dy as i64);
+            bounds_right = bounds_right.max(bounds_right + dx as i64);
+            bounds_bottom = bounds_bottom.max(bounds_bottom + dy as i64);
+        }
+
+        let background = self.text.effects.background.and_then(|(color, padding)| {
+            let padding = padding as i64;
+            let area = bounding_box(
+                bounds_left - padding,
+                bounds_top - padding,
+                bounds_right + padding + 1,
+                bounds_bottom + padding + 1,
+            )?;
+            Some((color, FilledRectangle(area)))
+        });
+
+        DecoratedTextIter {
+            background: background.map(|(color, area)| (color, area, area.pixels())),
+            layers: Some(Layers {
+                glyphs,
+                outlined,
+                origin: (left, top),
+                color: self.color,
+                effects: self.text.effects,
+                layer: Layer::Shadow,
+                index: 0,
+                glyphs_included: true,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Shadow,
+    Outline,
+    Glyphs,
+}
+
+/// The masks of the glyphs, walked once for every layer
+struct Layers {
+    glyphs: Mask,
+    /// The glyphs grown by the outline width, `None` without an outline
+    outlined: Option<Mask>,
+    origin: (i64, i64),
+    color: Color,
+    effects: TextEffects,
+    layer: Layer,
+    index: u64,
+    glyphs_included: bool,
+}
+
+impl Layers {
+    /// Moves a pixel of the masks to the buffer, pixels left of or above it are dropped
+    fn to_buffer(&self, p: Pixel, (dx, dy): (i32, i32)) -> Option<Pixel> {
+        let x = p.x() as i64 + self.origin.0 + dx as i64;
+        let y = p.y() as i64 + self.origin.1 + dy as i64;
+        if x < 0 || y < 0 {
+            return None;
+        }
+        Some(Pixel((x as u32, y as u32)))
+    }
+
+    /// The pixel of the current layer at `p` of the masks, if it has one
+    fn colored(&self, p: Pixel) -> Option<(Pixel, Color)> {
+        let outlined = self.outlined.as_ref().unwrap_or(&self.glyphs);
+        match self.layer {
+            Layer::Shadow => {
+                let (color, (dx, dy)) = self.effects.shadow?;
+                // the shadow is hidden behind the outlined glyphs
+                let (x, y) = (p.x() as i64 + dx as i64, p.y() as i64 + dy as i64);
+                let hidden = x >= 0 && y >= 0 && outlined.contains(Pixel((x as u32, y as u32)));
+                if !outlined.contains(p) || hidden {
+                    return None;
+                }
+                Some((self.to_buffer(p, (dx, dy))?, color))
+            }
+            Layer::Outline => {
+                let (color, _) = self.effects.outline?;
+                if !outlined.contains(p) || self.glyphs.contains(p) {
+                    return None;
+                }
+                Some((self.to_buffer(p, (0, 0))?, color))
+            }
+            Layer::Glyphs => {
+                if !self.glyphs.contains(p) {
+                    return None;
+                }
+                Some((self.to_buffer(p, (0, 0))?, self.color))
+            }
+        }
+    }
+}
+
+impl Iterator for Layers {
+    type Item = (Pixel, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (width, height) = (self.glyphs.width() as u64, self.glyphs.height() as u64);
+        loop {
+            if self.index >= width * height {
+                self.layer = match self.layer {
+                    Layer::Shadow => Layer::Outline,
+                    Layer::Outline if self.glyphs_included => Layer::Glyphs,
+                    Layer::Outline | Layer::Glyphs => return None,
+                };
+                self.index = 0;
+                continue;
+            }
+
+            let p = Pixel(((self.index % width) as u32, (self.index / width) as u32));
+            self.index += 1;
+            if let Some(colored) = self.colored(p) {
+                return Some(colored);
+            }
+        }
+    }
+}
+
+/// Lazily yields the background, then the shadow, the outline and the glyphs
+pub struct DecoratedTextIter {
+    background: Option<(Color, FilledRectangle, FilledRectangleIter)>,
+    layers: Option<Layers>,
+}
+
+impl DecoratedTextIter {
+    /// Takes the background out before iterating, to fill it as a rectangle
+    /// instead of pixel by pixel. The iterator then only yields the layers above it.
+    pub fn take_background(&mut self) -> Option<(Color, FilledRectangle)> {
+        self.background.take().map(|(color, area, _)| (color, area))
+    }
+
+    /// Stops before the glyphs, to draw them another way, e.g. anti-aliased
+    pub(crate) fn without_glyphs(mut self) -> Self {
+        if let Some(layers) = &mut self.layers {
+            layers.glyphs_included = false;
+        }
+        self
+    }
+}
+
+impl Iterator for DecoratedTextIter {
+    type Item = (Pixel, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((color, _, pixels)) = &mut self.background {
+            match pixels.next() {
+                Some(p) => return Some((p, *color)),
+                None => self.background = None,
+            }
+        }
+
+        self.layers.as_mut()?.next()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn effects_surround_glyphs() {
+        // Arrange
+        let text = Text::new((20, 20).into(), "Hi!", 16);
+        let effects = TextEffects::new()
+            .with_outline(Color::BLACK, 1)
+            .with_shadow(Color::GRAY, (2, 2))
+            .with_background(Color::BLUE, 3);
+
+        // Act
+        let pixels: Vec<(Pixel, Color)> = text
+            .with_effects(effects)
+            .decorated(Color::WHITE)
+            .colored_pixels()
+            .collect();
+
+        // Assert
+        let color_at = |pixel: Pixel| {
+            pixels
+                .iter()
+                .rev()
+                .find(|(p, _)| *p == pixel)
+                .map(|(_, color)| *color)
+        };
+        let glyphs: Vec<Pixel> = Text::new((20, 20).into(), "Hi!", 16).pixels().collect();
+        for glyph in &glyphs {
+            let neighbours = [
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ];
+            for (dx, dy) in neighbours.iter() {
+                let p = Pixel((
+                    (glyph.x() as i32 + dx) as u32,
+                    (glyph.y() as i32 + dy) as u32,
+                ));
+                let color = color_at(p).unwrap();
+                assert!(color == Color::WHITE || color == Color::BLACK);
+            }
+        }
+
+        let left = glyphs.iter().map(|p| p.x()).min().unwrap();
+        let top = glyphs.iter().map(|p| p.y()).min().unwrap();
+        let corner = Pixel((left - 4, top - 4));
+        assert_eq!(color_at(corner), Some(Color::BLUE));
+        assert_eq!(color_at(corner - (1, 1)), None);
+        assert!(pixels.iter().any(|(_, color)| *color == Color::GRAY));
+    }
+
+    #[test]
+    fn effects_follow_the_text() {
+        // Arrange
+        let effects = TextEffects::new()
+            .with_outline(Color::BLACK, 2)
+            .with_shadow(Color::GRAY, (-3, 1))
+            .with_background(Color::BLUE, 1);
+        let draw = |x, y| {
+            let mut pixels: Vec<(Pixel, Color)> = Text::new((x, y).into(), "Hi", 16)
+                .with_effects(effects)
+                .decorated(Color::WHITE)
+                .colored_pixels()
+                .collect();
+            pixels.sort_by_key(|(p, _)| (p.y(), p.x()));
+            pixels
+        };
+
+        // Act
+        let near = draw(10, 10);
+        let far = draw(1810, 1010);
+        let at_origin = draw(0, 0);
+
+        // Assert
+        let moved: Vec<(Pixel, Color)> = near
+            .iter()
+            .map(|(p, color)| (*p + (1800, 1000), *color))
+            .collect();
+        assert_eq!(far, moved);
+        assert!(at_origin.len() < near.len());
+        assert!(at_origin.iter().any(|(p, _)| p.x() == 0));
+    }
+
+    #[test]
+    fn background_is_taken_as_a_rectangle() {
+        // Arrange
+        let effects = TextEffects::new()
+            .with_outline(Color::BLACK, 1)
+            .with_background(Color::BLUE, 2);
+        let text = Text::new((10, 10).into(), "Hi", 16)
+            .with_effects(effects)
+            .decorated(Color::WHITE);
+
+        // Act
+        let mut layers = text.colored_pixels();
+        let (color, background) = layers.take_background().unwrap();
+
+        // Assert
+        let all: Vec<(Pixel, Color)> = text.colored_pixels().collect();
+        let rest: Vec<(Pixel, Color)> = layers.collect();
+        let filled: Vec<(Pixel, Color)> = background.pixels().map(|p| (p, color)).collect();
+        assert_eq!(color, Color::BLUE);
+        assert_eq!(all, [filled, rest.clone()].concat());
+        assert!(rest.iter().all(|(p, _)| background.0.contains(*p)));
+    }
+}
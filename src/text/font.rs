@@ -0,0 +1,293 @@
+use std::{
+    fs, io,
+    path::Path,
+    str::Chars,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use lazy_static::lazy_static;
+use rusttype::{point, GlyphId, PositionedGlyph, Scale, VMetrics};
+
+use super::{bitmap::BitmapFont, cache::Bitmap};
+
+lazy_static! {
+    static ref INTER: Face = {
+        let font_data = include_bytes!("Inter-SemiBold.ttf");
+        Face {
+            id: 0,
+            glyphs: Glyphs::Outline(rusttype::Font::try_from_bytes(font_data).unwrap()),
+        }
+    };
+}
+
+/// The id of the next loaded font, 0 is the embedded Inter
+static NEXT_FACE_ID: AtomicUsize = AtomicUsize::new(1);
+
+#[derive(Clone)]
+enum Glyphs {
+    /// Scalable outlines, rasterized at the size they are drawn at
+    Outline(rusttype::Font<'static>),
+    /// Fixed pixels, scaled by whole numbers
+    Bitmap(Arc<BitmapFont>),
+}
+
+/// A single loaded font, the id tells fonts apart in the glyph cache
+#[derive(Clone)]
+struct Face {
+    id: usize,
+    glyphs: Glyphs,
+}
+
+impl Face {
+    fn new(glyphs: Glyphs) -> Self {
+        Self {
+            id: NEXT_FACE_ID.fetch_add(1, Ordering::Relaxed),
+            glyphs,
+        }
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        match &self.glyphs {
+            Glyphs::Outline(font) => font.glyph(c).id() != GlyphId(0),
+            Glyphs::Bitmap(font) => font.has_glyph(c),
+        }
+    }
+
+    fn v_metrics(&self, height: u32) -> VMetrics {
+        match &self.glyphs {
+            Glyphs::Outline(font) => font.v_metrics(Scale::uniform(height as f32)),
+            Glyphs::Bitmap(font) => {
+                let scale = font.scale_for(height) as f32;
+                VMetrics {
+                    ascent: font.ascent() as f32 * scale,
+                    descent: -(font.descent() as f32) * scale,
+                    line_gap: 0.0,
+                }
+            }
+        }
+    }
+}
+
+/// A font with a chain of fallbacks, which are used for glyphs missing from it.
+/// Cloning is cheap, as the font data is shared.
+#[derive(Clone)]
+pub struct Font {
+    faces: Arc<[Face]>,
+}
+
+impl Font {
+    /// Parses a TrueType or OpenType font
+    pub fn from_bytes(data: Vec<u8>) -> io::Result<Self> {
+        match rusttype::Font::try_from_vec(data) {
+            Some(font) => Ok(Self {
+                faces: vec![Face::new(Glyphs::Outline(font))].into(),
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a TrueType or OpenType font",
+            )),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    /// Appends the fallback and its own fallbacks to the chain
+    pub fn with_fallback(mut self, fallback: Font) -> Self {
+        let mut faces = self.faces.to_vec();
+        faces.extend(fallback.faces.iter().cloned());
+        self.faces = faces.into();
+        self
+    }
+
+    /// Whether any font in the chain has a glyph for the character
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.faces.iter().any(|face| face.has_glyph(c))
+    }
+
+    /// The vertical metrics of the first font in the chain
+    pub(crate) fn v_metrics(&self, height: u32) -> VMetrics {
+        self.faces[0].v_metrics(height)
+    }
+
+    /// Lays out a single line, the top of the line is at `y = 0`
+    pub(crate) fn layout<'a>(&self, content: &'a str, height: u32) -> Layout<'a> {
+        Layout {
+            faces: self.faces.clone(),
+            chars: content.chars(),
+            height,
+            baseline: self.v_metrics(height).ascent,
+            caret: 0.0,
+            last: None,
+        }
+    }
+}
+
+/// The embedded Inter SemiBold
+impl Default for Font {
+    fn default() -> Self {
+        Self {
+            faces: vec![INTER.clone()].into(),
+        }
+    }
+}
+
+/// Bitmap fonts can be used wherever fonts are, also as fallbacks
+impl From<BitmapFont> for Font {
+    fn from(font: BitmapFont) -> Self {
+        Self {
+            faces: vec![Face::new(Glyphs::Bitmap(Arc::new(font)))].into(),
+        }
+    }
+}
+
+/// A glyph placed on the line
+pub(crate) enum PlacedGlyph {
+    /// Rasterized when drawn, the id of its font is the key into the glyph cache
+    Outline {
+        face: usize,
+        glyph: PositionedGlyph<'static>,
+    },
+    /// Pixels of a bitmap font, scaled by a whole number
+    Bitmap {
+        min: (i32, i32),
+        bitmap: Arc<Bitmap>,
+        scale: u32,
+    },
+}
+
+impl PlacedGlyph {
+    /// The left, top and exclusive right, bottom of the drawn pixels
+    pub(crate) fn pixel_bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        match self {
+            PlacedGlyph::Outline { glyph, .. } => glyph
+                .pixel_bounding_box()
+                .map(|bb| (bb.min.x, bb.min.y, bb.max.x, bb.max.y)),
+            PlacedGlyph::Bitmap { min, bitmap, scale } => {
+                if bitmap.width == 0 || bitmap.coverage.is_empty() {
+                    return None;
+                }
+
+                let width = (bitmap.width as u32 * scale) as i32;
+                let height = ((bitmap.coverage.len() / bitmap.width) as u32 * scale) as i32;
+                Some((min.0, min.1, min.0 + width, min.1 + height))
+            }
+        }
+    }
+}
+
+/// Lazily positions one glyph per character, each taken from the first font having it.
+/// Without any font having it, the missing glyph of the first font is shown.
+pub(crate) struct Layout<'a> {
+    faces: Arc<[Face]>,
+    chars: Chars<'a>,
+    height: u32,
+    baseline: f32,
+    caret: f32,
+    last: Option<(usize, GlyphId)>,
+}
+
+impl<'a> Layout<'a> {
+    /// Moves all glyphs by the offset
+    pub(crate) fn offset(mut self, x: f32, y: f32) -> Self {
+        self.caret += x;
+        self.baseline += y;
+        self
+    }
+
+    /// Where the caret ends up after all glyphs, without positioning them
+    pub(crate) fn advance_width(mut self) -> f32 {
+        while self.step(false).is_some() {}
+        self.caret
+    }
+
+    /// Where the caret ends up after the next character
+    pub(crate) fn next_caret(&mut self) -> Option<f32> {
+        self.step(false)?;
+        Some(self.caret)
+    }
+
+    /// Moves the caret past the next character, choosing the font for it.
+    /// Returns None at the end, and the glyph only if `place` is set and there is one.
+    fn step(&mut self, place: bool) -> Option<Option<PlacedGlyph>> {
+        let c = self.chars.next()?;
+        let index = self
+            .faces
+            .iter()
+            .position(|face| face.has_glyph(c))
+            .unwrap_or(0);
+        let face = &self.faces[index];
+
+        let font = match &face.glyphs {
+            Glyphs::Outline(font) => font,
+            Glyphs::Bitmap(font) => {
+                self.last = None;
+                let scale = font.scale_for(self.height);
+                let glyph = match font.glyph(c) {
+                    Some(glyph) => glyph,
+                    // bitmap fonts have no missing glyph, a gap is left instead
+                    None => {
+                        self.caret += (font.line_height() * scale) as f32 / 2.0;
+                        return Some(None);
+                    }
+                };
+
+                let (x, y) = (self.caret.round() as i32, self.baseline.round() as i32);
+                self.caret += (glyph.advance * scale) as f32;
+
+                if !place {
+                    return Some(None);
+                }
+
+                return Some(Some(PlacedGlyph::Bitmap {
+                    min: (
+                        x + glyph.bitmap.min.0 * scale as i32,
+                        y + glyph.bitmap.min.1 * scale as i32,
+                    ),
+                    bitmap: glyph.bitmap.clone(),
+                    scale,
+                }));
+            }
+        };
+
+        let scale = Scale::uniform(self.height as f32);
+        let glyph = font.glyph(c).scaled(scale);
+
+        // kerning only applies between glyphs of the same font
+        if let Some((last_index, last_id)) = self.last {
+            if last_index == index {
+                self.caret += font.pair_kerning(scale, last_id, glyph.id());
+            }
+        }
+
+        let caret = self.caret;
+        self.caret += glyph.h_metrics().advance_width;
+        self.last = Some((index, glyph.id()));
+
+        if !place {
+            return Some(None);
+        }
+
+        Some(Some(PlacedGlyph::Outline {
+            face: face.id,
+            glyph: glyph.positioned(point(caret, self.baseline)),
+        }))
+    }
+}
+
+impl<'a> Iterator for Layout<'a> {
+    type Item = PlacedGlyph;
+
+    // follows `rusttype::LayoutIter`, choosing the font per character
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(glyph) = self.step(true)? {
+                return Some(glyph);
+            }
+        }
+    }
+}
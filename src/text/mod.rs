@@ -1,57 +1,330 @@
-use lazy_static::lazy_static;
-use rusttype::{point, Font, PositionedGlyph, Scale};
+use crate::{
+    color::Color,
+    draw::{CoverageDrawable, Drawable},
+    pixel::Pixel,
+    primitives::{bounding_box, HitTest, Rectangle},
+};
 
-use crate::{draw::Drawable, pixel::Pixel};
+mod bitmap;
+pub use bitmap::BitmapFont;
 
-lazy_static! {
-    static ref FONT: Font<'static> = {
-        let font_data = include_bytes!("Inter-SemiBold.ttf");
-        Font::try_from_bytes(font_data).unwrap()
-    };
+mod cache;
+pub use cache::{CacheStats, GlyphCache};
+
+mod effects;
+pub use effects::{DecoratedText, DecoratedTextIter, TextEffects};
+
+mod font;
+pub use font::Font;
+
+mod rich;
+pub use rich::{RichText, RichTextIter, Segment, Style};
+
+mod text_box;
+pub use text_box::{Overflow, TextBox, TextBoxIter};
+
+use cache::Bitmap;
+use font::{Layout, PlacedGlyph};
+use std::sync::Arc;
+
+/// Which part of the text is placed at its position horizontally
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
 }
 
+/// Which part of the text is placed at its position vertically
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerticalAlign {
+    /// The top of the line, above the highest glyphs
+    Top,
+    /// The line the glyphs sit on
+    Baseline,
+    /// The bottom of the line, below the lowest glyphs
+    Bottom,
+}
+
+/// Sizes of a line of text in pixels, for placing it before it is drawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextMetrics {
+    /// How far the caret moves, where the next text would start
+    pub advance_width: f32,
+    /// How far the line reaches above the baseline
+    pub ascent: f32,
+    /// How far the line reaches below the baseline, negative
+    pub descent: f32,
+    /// The recommended space between the descent of a line and the ascent of the next
+    pub line_gap: f32,
+    /// The drawn pixels, None for blank text
+    pub bounding_box: Option<Rectangle>,
+}
+
+#[derive(Clone)]
 pub struct Text<'a> {
     pub(crate) position: Pixel,
     pub(crate) content: &'a str,
     pub(crate) height: u32,
+    pub(crate) font: Font,
+    pub(crate) horizontal: HorizontalAlign,
+    pub(crate) vertical: VerticalAlign,
+    pub(crate) cache: Option<GlyphCache>,
+    pub(crate) anti_aliased: bool,
+    pub(crate) effects: TextEffects,
 }
 
-impl<'a> Drawable for Text<'a> {
-    type IntoIter = std::vec::IntoIter<Pixel>;
+impl<'a> Text<'a> {
+    /// A single line of text in the default font, `height` is the size of the font in pixels
+    pub fn new(top_left: Pixel, content: &'a str, height: u32) -> Self {
+        Self {
+            position: top_left,
+            content,
+            height,
+            font: Font::default(),
+            horizontal: HorizontalAlign::Left,
+            vertical: VerticalAlign::Top,
+            cache: None,
+            anti_aliased: false,
+            effects: TextEffects::new(),
+        }
+    }
+
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Takes the glyphs from the cache, instead of rasterizing them again for every draw
+    pub fn with_cache(mut self, cache: GlyphCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Places the given part of the text at its position, instead of the top left
+    pub fn with_align(mut self, horizontal: HorizontalAlign, vertical: VerticalAlign) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    /// Blends the edges of the glyphs by how much they cover each pixel, when drawn to a buffer
+    pub fn with_anti_aliasing(mut self) -> Self {
+        self.anti_aliased = true;
+        self
+    }
+
+    /// Draws an outline, shadow or background box below the glyphs, when drawn to a buffer
+    pub fn with_effects(mut self, effects: TextEffects) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// The text in the color with its effects, as colored pixels
+    pub fn decorated(self, color: impl Into<Color>) -> DecoratedText<'a> {
+        DecoratedText {
+            text: self,
+            color: color.into(),
+        }
+    }
+
+    pub fn metrics(&self) -> TextMetrics {
+        let v_metrics = self.font.v_metrics(self.height);
+
+        TextMetrics {
+            advance_width: self.font.layout(self.content, self.height).advance_width(),
+            ascent: v_metrics.ascent,
+            descent: v_metrics.descent,
+            line_gap: v_metrics.line_gap,
+            bounding_box: self.bounding_box(),
+        }
+    }
 
     // https://docs.rs/imageproc/0.22.0/src/imageproc/drawing/text.rs.html#52-70
-    fn pixels(&self) -> Self::IntoIter {
-        let font = &FONT;
-        let scale = Scale {
-            x: self.height as f32,
-            y: self.height as f32,
+    fn layout(&self) -> Layout<'a> {
+        let layout = self.font.layout(self.content, self.height);
+
+        // only measured, glyphs are positioned once while drawing
+        let advance_width = || self.font.layout(self.content, self.height).advance_width();
+        let x = match self.horizontal {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => -advance_width() / 2.0,
+            HorizontalAlign::Right => -advance_width(),
+        };
+
+        let v_metrics = self.font.v_metrics(self.height);
+        let y = match self.vertical {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Baseline => -v_metrics.ascent,
+            VerticalAlign::Bottom => v_metrics.descent - v_metrics.ascent,
+        };
+
+        layout.offset(x.round(), y.round())
+    }
+}
+
+/// Text is picked by the box around all of its glyphs,
+/// so the gaps between letters still count as a hit
+impl<'a> HitTest for Text<'a> {
+    fn bounding_box(&self) -> Option<Rectangle> {
+        let (left, top, right, bottom) =
+            self.layout().filter_map(|glyph| glyph.pixel_bounds()).fold(
+                (i32::MAX, i32::MAX, i32::MIN, i32::MIN),
+                |(left, top, right, bottom), bounds| {
+                    (
+                        left.min(bounds.0),
+                        top.min(bounds.1),
+                        right.max(bounds.2),
+                        bottom.max(bounds.3),
+                    )
+                },
+            );
+
+        let (x, y) = (self.position.x() as i64, self.position.y() as i64);
+        bounding_box(
+            x + left as i64,
+            y + top as i64,
+            x + right as i64,
+            y + bottom as i64,
+        )
+    }
+
+    fn contains(&self, pixel: Pixel) -> bool {
+        match self.bounding_box() {
+            Some(bb) => self.contains_within(pixel, &bb),
+            None => false,
+        }
+    }
+
+    /// Laying out the text again for every pixel is avoided
+    fn contains_within(&self, pixel: Pixel, bounding_box: &Rectangle) -> bool {
+        bounding_box.contains(pixel)
+    }
+}
+
+/// Lazily lays out and rasterizes one glyph at a time, yielding the coverage of each pixel.
+/// Without a cache, the current glyph is kept in a scratch bitmap, allocated once per draw
+/// and reused for all glyphs.
+pub struct TextCoverageIter<'a> {
+    glyphs: Layout<'a>,
+    position: Pixel,
+    height: u32,
+    cache: Option<GlyphCache>,
+    bitmap: Arc<Bitmap>,
+    glyph_min: (i32, i32),
+    /// Each pixel of the bitmap covers `scale` by `scale` pixels
+    scale: usize,
+    index: usize,
+}
+
+impl<'a> TextCoverageIter<'a> {
+    fn new(text: &Text<'a>) -> Self {
+        Self {
+            glyphs: text.layout(),
+            position: text.position,
+            height: text.height,
+            cache: text.cache.clone(),
+            bitmap: Arc::default(),
+            glyph_min: (0, 0),
+            scale: 1,
+            index: 0,
+        }
+    }
+
+    /// Rasterizes or takes the next glyph from the cache
+    fn next_glyph(&mut self) -> bool {
+        let glyph = match self.glyphs.next() {
+            Some(glyph) => glyph,
+            None => return false,
         };
-        let v_metrics = font.v_metrics(scale);
-        let offset = point(0.0, v_metrics.ascent);
 
-        let glyphs: Vec<PositionedGlyph<'_>> = font.layout(self.content, scale, offset).collect();
-        let mut pixels: Vec<Pixel> = Vec::new();
+        self.index = 0;
+        self.scale = 1;
+        match (glyph, &self.cache) {
+            (PlacedGlyph::Bitmap { min, bitmap, scale }, _) => {
+                self.glyph_min = min;
+                self.bitmap = bitmap;
+                self.scale = scale as usize;
+            }
+            (PlacedGlyph::Outline { face, glyph }, Some(cache)) => {
+                let (min, bitmap) = cache.get(face, self.height, &glyph);
+                self.glyph_min = min;
+                self.bitmap = bitmap;
+            }
+            (PlacedGlyph::Outline { glyph, .. }, None) => {
+                let bitmap = Arc::make_mut(&mut self.bitmap);
+                bitmap.rasterize(&glyph);
+                self.glyph_min = bitmap.min;
+            }
+        }
 
-        for g in glyphs {
-            if let Some(bb) = g.pixel_bounding_box() {
-                g.draw(|x, y, z| {
-                    // early exit by coverage treshhold
-                    if z < 0.5 {
-                        return;
-                    }
+        true
+    }
+}
+
+impl<'a> Iterator for TextCoverageIter<'a> {
+    type Item = (Pixel, f32);
 
-                    let gx = x as i32 + bb.min.x;
-                    let gy = y as i32 + bb.min.y;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (scale, width) = (self.scale, self.bitmap.width * self.scale);
+            while self.index < self.bitmap.coverage.len() * scale * scale {
+                let (x, y) = (self.index % width, self.index / width);
+                self.index += 1;
 
-                    let pixel_x = gx as u32 + self.position.x();
-                    let pixel_y = gy as u32 + self.position.y();
+                let coverage = self.bitmap.coverage[y / scale * self.bitmap.width + x / scale];
+                if coverage <= 0.0 {
+                    continue;
+                }
 
-                    pixels.push((pixel_x, pixel_y).into());
-                })
+                let gx = x as i64 + self.glyph_min.0 as i64;
+                let gy = y as i64 + self.glyph_min.1 as i64;
+
+                // aligned text can reach past the left or top edge
+                let pixel_x = gx + self.position.x() as i64;
+                let pixel_y = gy + self.position.y() as i64;
+                if pixel_x < 0 || pixel_y < 0 {
+                    continue;
+                }
+
+                return Some(((pixel_x as u32, pixel_y as u32).into(), coverage));
+            }
+
+            if !self.next_glyph() {
+                return None;
             }
         }
+    }
+}
+
+/// Only yields the pixels covered at least by half, for crisp text without blending
+pub struct TextIter<'a>(TextCoverageIter<'a>);
+
+impl<'a> Iterator for TextIter<'a> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // treshhold the coverage
+        self.0
+            .find(|(_, coverage)| *coverage >= 0.5)
+            .map(|(pixel, _)| pixel)
+    }
+}
+
+impl<'a> Drawable for Text<'a> {
+    type IntoIter = TextIter<'a>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        TextIter(TextCoverageIter::new(self))
+    }
+}
 
-        pixels.into_iter()
+/// Smooth edges, when blended over the buffer
+impl<'a> CoverageDrawable for Text<'a> {
+    type Coverage = TextCoverageIter<'a>;
+
+    fn coverage(&self) -> Self::Coverage {
+        TextCoverageIter::new(self)
     }
 }
 
@@ -61,8 +334,100 @@ mod test {
 
     #[test]
     fn load_font() {
-        let _font = &FONT;
+        let font = Font::default();
+
+        assert!(font.has_glyph('A'));
+    }
+
+    #[test]
+    fn fallback_fonts() {
+        // Arrange, a bitmap font with a glyph missing from Inter
+        let inter = Font::default();
+        let bdf = bitmap::test::BDF.replacen("ENCODING 65", "ENCODING 20013", 1);
+        let bitmap = BitmapFont::from_bdf(&bdf).unwrap();
+        let invalid = Font::from_bytes(vec![0; 16]);
+
+        // Act
+        let chained = inter.clone().with_fallback(bitmap.into());
+        let count = |content, font: &Font| {
+            Text::new((0, 0).into(), content, 16)
+                .with_font(font.clone())
+                .pixels()
+                .count()
+        };
+
+        // Assert
+        assert!(!inter.has_glyph('\u{4e2d}'));
+        assert!(chained.has_glyph('\u{4e2d}'));
+        assert!(invalid.is_err());
+        assert_eq!(count("Aa", &chained), count("Aa", &inter));
+        // scaled by 4, each of the 6 pixels of the glyph covers 16 pixels
+        assert_eq!(count("\u{4e2d}", &chained), 6 * 16);
+        assert_eq!(count("Aa\u{4e2d}", &chained), count("Aa", &inter) + 6 * 16);
+    }
+
+    #[test]
+    fn bounding_box_covers_pixels() {
+        // Arrange
+        let text = Text::new((10, 10).into(), "Hit me", 16);
+
+        // Act
+        let bb = text.bounding_box().unwrap();
+
+        // Assert
+        assert!(text.pixels().all(|p| bb.contains(p)));
+        assert!(text.contains(bb.top_left));
+        assert!(text.intersects(&Rectangle::new(bb.top_left - (2, 2), 3, 3)));
+        assert!(!text.intersects(&Rectangle::new((0, 0).into(), 5, 5)));
+        assert!(Text::new((10, 10).into(), " ", 16).bounding_box().is_none());
+    }
+
+    #[test]
+    fn coverage_thresholds_to_pixels() {
+        // Arrange
+        let text = Text::new((4, 4).into(), "Smooth", 20);
+
+        // Act
+        let crisp: Vec<Pixel> = text.pixels().collect();
+        let half_covered: Vec<Pixel> = text
+            .coverage()
+            .filter(|(_, coverage)| *coverage >= 0.5)
+            .map(|(pixel, _)| pixel)
+            .collect();
+
+        // Assert
+        assert_eq!(crisp, half_covered);
+        assert!(text.coverage().count() > crisp.len());
+        assert!(text.coverage().all(|(_, c)| c > 0.0 && c <= 1.0));
+    }
+
+    #[test]
+    fn alignment() {
+        // Arrange
+        let text = |horizontal, vertical| {
+            Text::new((100, 100).into(), "Score: 42", 20).with_align(horizontal, vertical)
+        };
+
+        // Act
+        let metrics = text(HorizontalAlign::Left, VerticalAlign::Top).metrics();
+        let top_left = metrics.bounding_box.unwrap();
+        let centered = text(HorizontalAlign::Center, VerticalAlign::Baseline)
+            .bounding_box()
+            .unwrap();
+        let bottom_right = text(HorizontalAlign::Right, VerticalAlign::Bottom)
+            .bounding_box()
+            .unwrap();
 
-        assert!(true);
+        // Assert
+        let width = metrics.advance_width.round() as u32;
+        let ascent = metrics.ascent.round() as u32;
+        let line_height = (metrics.ascent - metrics.descent).round() as u32;
+        assert_eq!(centered.width, top_left.width);
+        assert_eq!(centered.top_left, top_left.top_left - (width / 2, ascent));
+        assert_eq!(
+            bottom_right.top_left,
+            top_left.top_left - (width, line_height)
+        );
+        assert!(metrics.descent < 0.0);
     }
 }
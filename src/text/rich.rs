@@ -0,0 +1,496 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    color::Color,
+    draw::{ColoredDrawable, Drawable},
+    image::Image,
+    pixel::Pixel,
+    primitives::{FilledRectangle, FilledRectangleIter, Rectangle},
+};
+
+use super::{Font, GlyphCache, HorizontalAlign, Text, TextIter, VerticalAlign};
+
+/// How a run of text is drawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Style<'a> {
+    pub color: Color,
+    /// The name of the font, None for the font of the rich text
+    pub font: Option<&'a str>,
+    pub height: u32,
+    pub underline: bool,
+}
+
+/// A part of the content, either text in a single style or an icon
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text { content: &'a str, style: Style<'a> },
+    Icon(&'a str),
+}
+
+/// Text with inline style changes, written as markup:
+///
+/// - `[color=yellow]` or `[color=#ffcc00]` until `[/color]`
+/// - `[font=name]` until `[/font]`, for fonts added with [`with_named_font`](Self::with_named_font)
+/// - `[size=24]` until `[/size]`
+/// - `[u]` until `[/u]` underlines the text
+/// - `[icon=name]` shows an icon added with [`with_icon`](Self::with_icon)
+/// - `[[` is a literal `[`
+///
+/// Tags can be nested, closing a tag restores the style before it was opened.
+/// Unknown tags and invalid values are shown as written, to make typos easy to spot.
+/// Lines are broken at newlines only.
+pub struct RichText<'a> {
+    pub(crate) position: Pixel,
+    pub(crate) content: &'a str,
+    pub(crate) height: u32,
+    pub(crate) color: Color,
+    pub(crate) font: Font,
+    pub(crate) fonts: HashMap<String, Font>,
+    pub(crate) icons: HashMap<String, Arc<Image>>,
+    pub(crate) cache: Option<GlyphCache>,
+}
+
+impl<'a> RichText<'a> {
+    /// White text in the default font, until the markup says otherwise
+    pub fn new(top_left: Pixel, content: &'a str, height: u32) -> Self {
+        Self {
+            position: top_left,
+            content,
+            height,
+            color: Color::WHITE,
+            font: Font::default(),
+            fonts: HashMap::new(),
+            icons: HashMap::new(),
+            cache: None,
+        }
+    }
+
+    /// The color outside of any `[color]` tag
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// The font outside of any `[font]` tag
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Makes the font available to `[font=name]`
+    pub fn with_named_font(mut self, name: &str, font: Font) -> Self {
+        self.fonts.insert(name.to_owned(), font);
+        self
+    }
+
+    /// Makes the image available to `[icon=name]`, e.g. the button to press.
+    /// Its bottom sits on the baseline, it is as wide as it advances the caret.
+    pub fn with_icon(mut self, name: &str, icon: Image) -> Self {
+        self.icons.insert(name.to_owned(), Arc::new(icon));
+        self
+    }
+
+    pub fn with_cache(mut self, cache: GlyphCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The content split at the tags, each text segment in the style it is drawn in
+    pub fn segments(&self) -> Vec<Segment<'a>> {
+        let content = self.content;
+        let base = Style {
+            color: self.color,
+            font: None,
+            height: self.height,
+            underline: false,
+        };
+
+        let mut segments = Vec::new();
+        let mut styles = vec![(Tag::Base, base)];
+        let mut start = 0;
+        let mut text_start = 0;
+
+        while let Some(found) = content[start..].find('[') {
+            let open = start + found;
+            let style = styles[styles.len() - 1].1;
+            let push = |segments: &mut Vec<Segment<'a>>, end: usize| {
+                if text_start < end {
+                    segments.push(Segment::Text {
+                        content: &content[text_start..end],
+                        style,
+                    });
+                }
+            };
+
+            // an escaped bracket starts the next segment
+            if content[open + 1..].starts_with('[') {
+                push(&mut segments, open + 1);
+                start = open + 2;
+                text_start = start;
+                continue;
+            }
+
+            let close = match content[open..].find(']') {
+                Some(close) => open + close,
+                None => break,
+            };
+
+            let tag = &content[open + 1..close];
+            let (name, value) = match tag.find('=') {
+                Some(i) => (&tag[..i], Some(tag[i + 1..].trim())),
+                None => (tag, None),
+            };
+
+            let name = name.trim();
+            let valid = if let Some(closing) = name.strip_prefix('/') {
+                match (Tag::closing(closing), value) {
+                    (Some(tag), None) => {
+                        push(&mut segments, open);
+                        // unmatched closing tags are dropped
+                        if let Some(i) = styles.iter().rposition(|(open, _)| *open == tag) {
+                            styles.truncate(i);
+                        }
+                        true
+                    }
+                    _ => false,
+                }
+            } else if let (Some(value), "icon") = (value, name) {
+                let valid = self.icons.contains_key(value);
+                if valid {
+                    push(&mut segments, open);
+                    segments.push(Segment::Icon(value));
+                }
+                valid
+            } else {
+                match self.opening(name, value, style) {
+                    Some(opened) => {
+                        push(&mut segments, open);
+                        styles.push(opened);
+                        true
+                    }
+                    None => false,
+                }
+            };
+
+            if valid {
+                start = close + 1;
+                text_start = start;
+            } else {
+                // not a valid tag, it stays part of the text
+                start = open + 1;
+            }
+        }
+
+        if text_start < content.len() {
+            segments.push(Segment::Text {
+                content: &content[text_start..],
+                style: styles[styles.len() - 1].1,
+            });
+        }
+
+        segments
+    }
+
+    /// The style inside of an opening tag, None for invalid tags
+    fn opening(
+        &self,
+        name: &str,
+        value: Option<&'a str>,
+        mut style: Style<'a>,
+    ) -> Option<(Tag, Style<'a>)> {
+        match (name, value) {
+            ("color", Some(value)) => {
+                style.color = Color::from_name(value).or_else(|| Color::from_hex(value))?;
+                Some((Tag::Color, style))
+            }
+            ("font", Some(value)) if self.fonts.contains_key(value) => {
+                style.font = Some(value);
+                Some((Tag::Font, style))
+            }
+            ("size", Some(value)) => {
+                style.height = value.parse().ok().filter(|size| *size > 0)?;
+                Some((Tag::Size, style))
+            }
+            ("u", None) => {
+                style.underline = true;
+                Some((Tag::Underline, style))
+            }
+            _ => None,
+        }
+    }
+
+    fn font_of(&self, style: &Style) -> &Font {
+        match style.font.and_then(|name| self.fonts.get(name)) {
+            Some(font) => font,
+            None => &self.font,
+        }
+    }
+
+    fn text(&self, position: Pixel, content: &'a str, style: &Style) -> Text<'a> {
+        let text = Text::new(position, content, style.height)
+            .with_font(self.font_of(style).clone())
+            .with_align(HorizontalAlign::Left, VerticalAlign::Baseline);
+        match &self.cache {
+            Some(cache) => text.with_cache(cache.clone()),
+            None => text,
+        }
+    }
+
+    /// The segments of each line, placed next to each other on a shared baseline.
+    /// Icons are returned with their top left corner, to be drawn as images.
+    pub(crate) fn layout(&self) -> (RichTextIter<'a>, Vec<(Pixel, Arc<Image>)>) {
+        let mut lines = vec![Vec::new()];
+        for segment in self.segments() {
+            match segment {
+                Segment::Text { content, style } => {
+                    for (i, line) in content.split('\n').enumerate() {
+                        if i > 0 {
+                            lines.push(Vec::new());
+                        }
+                        if !line.is_empty() {
+                            lines.last_mut().unwrap().push(Segment::Text {
+                                content: line,
+                                style,
+                            });
+                        }
+                    }
+                }
+                Segment::Icon(_) => lines.last_mut().unwrap().push(segment),
+            }
+        }
+
+        let mut pieces = Vec::new();
+        let mut icons = Vec::new();
+        let mut top = self.position.y() as f32;
+        for line in lines {
+            // ascent, descent and line gap of the tallest parts of the line
+            let extents = line
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Text { style, .. } => {
+                        let v_metrics = self.font_of(style).v_metrics(style.height);
+                        (v_metrics.ascent, v_metrics.descent, v_metrics.line_gap)
+                    }
+                    Segment::Icon(name) => (self.icons[*name].height() as f32, 0.0, 0.0),
+                })
+                .fold(
+                    None,
+                    |line: Option<(f32, f32, f32)>, (a, d, g)| match line {
+                        Some((ascent, descent, gap)) => {
+                            Some((ascent.max(a), descent.min(d), gap.max(g)))
+                        }
+                        None => Some((a, d, g)),
+                    },
+                );
+
+            // empty lines are as high as a line in the font of the rich text
+            let (ascent, descent, line_gap) = extents.unwrap_or_else(|| {
+                let v_metrics = self.font.v_metrics(self.height);
+                (v_metrics.ascent, v_metrics.descent, v_metrics.line_gap)
+            });
+
+            let baseline = (top + ascent).round() as u32;
+            let mut caret = self.position.x() as f32;
+            for segment in line {
+                let x = caret.round() as u32;
+                match segment {
+                    Segment::Text { content, style } => {
+                        let text = self.text(Pixel((x, baseline)), content, &style);
+                        caret += text.metrics().advance_width;
+
+                        if style.underline {
+                            let thickness = (style.height / 16).max(1);
+                            let offset = (style.height / 10).max(1);
+                            let width = caret.round() as u32 - x;
+                            let rect =
+                                Rectangle::new(Pixel((x, baseline + offset)), width, thickness);
+                            pieces.push(Piece::Underline(
+                                FilledRectangle(rect).pixels(),
+                                style.color,
+                            ));
+                        }
+                        pieces.push(Piece::Glyphs(text.pixels(), style.color));
+                    }
+                    Segment::Icon(name) => {
+                        let icon = self.icons[name].clone();
+                        caret += icon.width() as f32;
+                        // the line is at least as high as its icons, they stay below the top
+                        let top_left = Pixel((x, baseline.saturating_sub(icon.height())));
+                        icons.push((top_left, icon));
+                    }
+                }
+            }
+
+            top += ascent - descent + line_gap;
+        }
+
+        let glyphs = RichTextIter {
+            pieces: pieces.into_iter(),
+            current: None,
+        };
+        (glyphs, icons)
+    }
+}
+
+/// The kinds of tags, which change the style until they are closed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tag {
+    Base,
+    Color,
+    Font,
+    Size,
+    Underline,
+}
+
+impl Tag {
+    fn closing(name: &str) -> Option<Self> {
+        match name.trim() {
+            "color" => Some(Tag::Color),
+            "font" => Some(Tag::Font),
+            "size" => Some(Tag::Size),
+            "u" => Some(Tag::Underline),
+            _ => None,
+        }
+    }
+}
+
+/// A placed run of glyphs or underline, lazily yielding its pixels
+enum Piece<'a> {
+    Glyphs(TextIter<'a>, Color),
+    Underline(FilledRectangleIter, Color),
+}
+
+impl<'a> Iterator for Piece<'a> {
+    type Item = (Pixel, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Piece::Glyphs(pixels, color) => pixels.next().map(|pixel| (pixel, *color)),
+            Piece::Underline(pixels, color) => pixels.next().map(|pixel| (pixel, *color)),
+        }
+    }
+}
+
+/// Lazily draws one placed part after the other
+pub struct RichTextIter<'a> {
+    pieces: std::vec::IntoIter<Piece<'a>>,
+    current: Option<Piece<'a>>,
+}
+
+impl<'a> Iterator for RichTextIter<'a> {
+    type Item = (Pixel, Color);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut().and_then(|piece| piece.next()) {
+                return Some(item);
+            }
+
+            self.current = Some(self.pieces.next()?);
+        }
+    }
+}
+
+/// The glyphs and underlines, icons are left out,
+/// as [`Buffer::draw_rich_text`](crate::buffer::Buffer::draw_rich_text) draws them as images
+impl<'a> ColoredDrawable for RichText<'a> {
+    type Colored = RichTextIter<'a>;
+
+    fn colored_pixels(&self) -> Self::Colored {
+        self.layout().0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn style(color: Color, height: u32, underline: bool) -> Style<'static> {
+        Style {
+            color,
+            font: None,
+            height,
+            underline,
+        }
+    }
+
+    #[test]
+    fn parses_markup() {
+        // Arrange
+        let icon = Image::new(1, 1, vec![Color::RED]).unwrap();
+        let text = RichText::new(
+            (0, 0).into(),
+            "Press [color=yellow]Space [icon=key][u]now[/u][/color] [[or] [b]x[/size]",
+            16,
+        )
+        .with_icon("key", icon);
+
+        // Act
+        let segments = text.segments();
+
+        // Assert
+        let white = style(Color::WHITE, 16, false);
+        let yellow = style(Color::YELLOW, 16, false);
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Text {
+                    content: "Press ",
+                    style: white
+                },
+                Segment::Text {
+                    content: "Space ",
+                    style: yellow
+                },
+                Segment::Icon("key"),
+                Segment::Text {
+                    content: "now",
+                    style: style(Color::YELLOW, 16, true)
+                },
+                Segment::Text {
+                    content: " [",
+                    style: white
+                },
+                Segment::Text {
+                    content: "or] [b]x",
+                    style: white
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn draws_styled_runs() {
+        // Arrange
+        let icon = Image::new(4, 4, vec![Color::BLUE; 16]).unwrap();
+        let text = RichText::new(
+            (10, 10).into(),
+            "A[color=red]B[size=32]C[/size][/color][icon=i]",
+            16,
+        )
+        .with_icon("i", icon);
+
+        // Act
+        let (glyphs, icons) = text.layout();
+        let pixels: Vec<(Pixel, Color)> = glyphs.collect();
+
+        // Assert
+        let count = |color| pixels.iter().filter(|(_, c)| *c == color).count();
+        let plain = Text::new((0, 0).into(), "A", 16).pixels().count();
+        assert_eq!(count(Color::WHITE), plain);
+        assert!(count(Color::RED) > 2 * plain);
+        assert_eq!(count(Color::BLUE), 0);
+        assert_eq!(icons.len(), 1);
+
+        // the icon sits on the baseline shared with the larger text
+        let (top_left, icon) = &icons[0];
+        let icon_bottom = top_left.y() + icon.height() - 1;
+        let white_bottom = pixels
+            .iter()
+            .filter(|(_, c)| *c == Color::WHITE)
+            .map(|(p, _)| p.y())
+            .max()
+            .unwrap();
+        assert_eq!(icon_bottom, white_bottom);
+    }
+}
@@ -0,0 +1,398 @@
+use crate::{
+    draw::{CoverageDrawable, Drawable},
+    pixel::Pixel,
+    primitives::Rectangle,
+};
+
+use super::{Font, GlyphCache, HorizontalAlign, Text, TextCoverageIter, TextIter, VerticalAlign};
+
+/// What happens to the lines, which do not fit into the box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Lines are cut off at the bottom edge of the box
+    Clip,
+    /// Only lines fitting completely are shown, the last one ends with an ellipsis
+    Ellipsis,
+}
+
+/// Text wrapped into a box, e.g. for dialogs and help screens.
+/// Lines break at newlines and between words, words too long for a line are broken anywhere.
+pub struct TextBox<'a> {
+    pub area: Rectangle,
+    pub content: &'a str,
+    pub height: u32,
+    pub font: Font,
+    pub align: HorizontalAlign,
+    /// Multiplies the distance between lines recommended by the font
+    pub line_spacing: f32,
+    pub overflow: Overflow,
+    pub cache: Option<GlyphCache>,
+}
+
+impl<'a> TextBox<'a> {
+    /// Left aligned text in the default font, clipped at the bottom of the area
+    pub fn new(area: Rectangle, content: &'a str, height: u32) -> Self {
+        Self {
+            area,
+            content,
+            height,
+            font: Font::default(),
+            align: HorizontalAlign::Left,
+            line_spacing: 1.0,
+            overflow: Overflow::Clip,
+            cache: None,
+        }
+    }
+
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn with_align(mut self, align: HorizontalAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: GlyphCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    fn width_of(&self, text: &str) -> f32 {
+        self.font.layout(text, self.height).advance_width()
+    }
+
+    /// The text split into lines, which fit into the width of the box
+    pub fn wrapped_lines(&self) -> Vec<&'a str> {
+        let mut lines = Vec::new();
+        for paragraph in self.content.lines() {
+            self.wrap(paragraph, &mut lines);
+        }
+
+        lines
+    }
+
+    /// The length in bytes and the width of the longest start of the text, which fits
+    fn fitting(&self, text: &str, max_width: f32) -> (usize, f32) {
+        let mut layout = self.font.layout(text, self.height);
+        let mut fitting = (0, 0.0);
+        for (i, c) in text.char_indices() {
+            match layout.next_caret() {
+                Some(caret) if caret <= max_width => fitting = (i + c.len_utf8(), caret),
+                _ => break,
+            }
+        }
+
+        fitting
+    }
+
+    /// Greedily fills each line with as many words as fit.
+    /// Words are measured once, kerning across the spaces between them is ignored.
+    fn wrap(&self, paragraph: &'a str, lines: &mut Vec<&'a str>) {
+        let max_width = self.area.width as f32;
+        // the start, end and width of the current line
+        let mut line: Option<(usize, usize, f32)> = None;
+
+        for (start, end) in words(paragraph) {
+            let mut word_width = self.width_of(&paragraph[start..end]);
+            if let Some((line_start, line_end, line_width)) = line {
+                let width = line_width + self.width_of(&paragraph[line_end..start]) + word_width;
+                if width <= max_width {
+                    line = Some((line_start, end, width));
+                    continue;
+                }
+
+                lines.push(&paragraph[line_start..line_end]);
+            }
+
+            // a word too long for a line of its own is broken between characters
+            let mut start = start;
+            while word_width > max_width {
+                let word = &paragraph[start..end];
+                let (mut fitting, mut fitting_width) = self.fitting(word, max_width);
+                if fitting == 0 {
+                    // at least one character per line
+                    fitting = word.chars().next().map_or(0, char::len_utf8);
+                    fitting_width = self.width_of(&word[..fitting]);
+                }
+
+                if start + fitting >= end {
+                    break;
+                }
+
+                lines.push(&word[..fitting]);
+                start += fitting;
+                word_width -= fitting_width;
+            }
+            line = Some((start, end, word_width));
+        }
+
+        match line {
+            Some((start, end, _)) => lines.push(&paragraph[start..end]),
+            None => lines.push(""),
+        }
+    }
+
+    /// The lines placed inside of the box, after handling the overflow
+    pub fn lines(&self) -> Vec<Text<'a>> {
+        let v_metrics = self.font.v_metrics(self.height);
+        let line_height = v_metrics.ascent - v_metrics.descent;
+        let distance = (line_height + v_metrics.line_gap) * self.line_spacing;
+        let box_height = self.area.height as f32;
+
+        let mut lines = self.wrapped_lines();
+        let fitting = lines
+            .iter()
+            .enumerate()
+            .take_while(|(i, _)| *i as f32 * distance + line_height <= box_height)
+            .count();
+
+        let mut ellipsis = None;
+        if fitting < lines.len() {
+            match self.overflow {
+                Overflow::Clip => {
+                    // the partly visible lines are cut off by the pixels
+                    let visible = (0..lines.len())
+                        .take_while(|i| (*i as f32 * distance) < box_height)
+                        .count();
+                    lines.truncate(visible);
+                }
+                Overflow::Ellipsis => {
+                    lines.truncate(fitting);
+                    if let Some(last) = lines.last_mut() {
+                        let (line, dots) = self.with_ellipsis(last);
+                        *last = line;
+                        ellipsis = Some(dots);
+                    }
+                }
+            }
+        }
+
+        let last_index = lines.len().saturating_sub(1);
+        let mut texts = Vec::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let dots = ellipsis.filter(|_| i == last_index);
+            let line_width = self.width_of(line);
+            let dots_width = dots.map_or(0.0, |dots| self.width_of(dots));
+            let width = line_width + dots_width;
+            let offset = match self.align {
+                HorizontalAlign::Left => 0.0,
+                HorizontalAlign::Center => (self.area.width as f32 - width) / 2.0,
+                HorizontalAlign::Right => self.area.width as f32 - width,
+            };
+
+            let left = self.area.top_left.x() as f32 + offset;
+            let y = self.area.top_left.y() + (i as f32 * distance).round() as u32;
+
+            if left >= 0.0 {
+                let position = Pixel((left.round() as u32, y));
+                texts.push(self.text(position, line));
+                if let Some(dots) = dots {
+                    let position = position + (line_width.round() as u32, 0);
+                    texts.push(self.text(position, dots));
+                }
+            } else {
+                // lines wider than the box are placed by their right end,
+                // the part left of the box is clipped like overflowing lines
+                let right = (left + width).round();
+                let align =
+                    |text: Text<'a>| text.with_align(HorizontalAlign::Right, VerticalAlign::Top);
+                let line_end = Pixel(((right - dots_width).round().max(0.0) as u32, y));
+                texts.push(align(self.text(line_end, line)));
+                if let Some(dots) = dots {
+                    texts.push(align(self.text(Pixel((right as u32, y)), dots)));
+                }
+            }
+        }
+
+        texts
+    }
+
+    fn text(&self, position: Pixel, line: &'a str) -> Text<'a> {
+        let text = Text::new(position, line, self.height).with_font(self.font.clone());
+        match &self.cache {
+            Some(cache) => text.with_cache(cache.clone()),
+            None => text,
+        }
+    }
+
+    /// Shortens the line, until the ellipsis fits behind it
+    fn with_ellipsis(&self, line: &'a str) -> (&'a str, &'static str) {
+        let dots = if self.font.has_glyph('…') {
+            "…"
+        } else {
+            "..."
+        };
+        let max_width = self.area.width as f32 - self.width_of(dots);
+        let (fitting, _) = self.fitting(line, max_width);
+
+        (line[..fitting].trim_end(), dots)
+    }
+}
+
+/// The start and exclusive end of each word, separated by whitespace
+fn words(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (start, c.is_whitespace()) {
+            (None, false) => {
+                start = Some(i);
+                None
+            }
+            (Some(word_start), true) => {
+                start = None;
+                Some((word_start, i))
+            }
+            _ => None,
+        })
+}
+
+/// Lazily draws one line after the other, dropping the pixels outside of the box
+pub struct TextBoxIter<'a, I> {
+    lines: std::vec::IntoIter<Text<'a>>,
+    current: Option<I>,
+    start: fn(&Text<'a>) -> I,
+    area: Rectangle,
+}
+
+impl<'a, I: Iterator> TextBoxIter<'a, I> {
+    fn new(text_box: &TextBox<'a>, start: fn(&Text<'a>) -> I) -> Self {
+        Self {
+            lines: text_box.lines().into_iter(),
+            current: None,
+            start,
+            area: text_box.area,
+        }
+    }
+
+    fn find(&mut self, pixel: fn(&I::Item) -> Pixel) -> Option<I::Item> {
+        loop {
+            if let Some(current) = self.current.as_mut() {
+                let area = self.area;
+                if let Some(item) = current.find(|item| area.contains(pixel(item))) {
+                    return Some(item);
+                }
+            }
+
+            let line = self.lines.next()?;
+            self.current = Some((self.start)(&line));
+        }
+    }
+}
+
+impl<'a> Iterator for TextBoxIter<'a, TextIter<'a>> {
+    type Item = Pixel;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.find(|pixel| *pixel)
+    }
+}
+
+impl<'a> Iterator for TextBoxIter<'a, TextCoverageIter<'a>> {
+    type Item = (Pixel, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.find(|(pixel, _)| *pixel)
+    }
+}
+
+impl<'a> Drawable for TextBox<'a> {
+    type IntoIter = TextBoxIter<'a, TextIter<'a>>;
+
+    fn pixels(&self) -> Self::IntoIter {
+        TextBoxIter::new(self, |text| text.pixels())
+    }
+}
+
+impl<'a> CoverageDrawable for TextBox<'a> {
+    type Coverage = TextBoxIter<'a, TextCoverageIter<'a>>;
+
+    fn coverage(&self) -> Self::Coverage {
+        TextBoxIter::new(self, |text| text.coverage())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitives::HitTest;
+
+    #[test]
+    fn wraps_words_and_newlines() {
+        // Arrange
+        let area = Rectangle::new((10, 10).into(), 120, 200);
+        let text_box = TextBox::new(area, "The quick brown fox jumps\n\nover the lazy dog", 16);
+
+        // Act
+        let lines = text_box.wrapped_lines();
+
+        // Assert
+        assert!(lines.len() > 3);
+        assert!(lines.contains(&""));
+        assert!(lines.iter().all(|line| text_box.width_of(line) <= 120.0));
+        assert_eq!(lines.join(" ").split_whitespace().count(), 9);
+        assert!(text_box.pixels().all(|p| area.contains(p)));
+    }
+
+    #[test]
+    fn breaks_long_words() {
+        // Arrange
+        let area = Rectangle::new((0, 0).into(), 40, 200);
+        let text_box = TextBox::new(area, "Supercalifragilistic", 16);
+
+        // Act
+        let lines = text_box.wrapped_lines();
+
+        // Assert
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), "Supercalifragilistic");
+    }
+
+    #[test]
+    fn ellipsis_on_overflow() {
+        // Arrange
+        let area = Rectangle::new((0, 0).into(), 100, 40);
+        let content = "A long story, which does not fit into a small dialog box";
+        let text_box = TextBox::new(area, content, 16).with_overflow(Overflow::Ellipsis);
+
+        // Act
+        let lines = text_box.lines();
+
+        // Assert
+        let last = lines.last().unwrap();
+        assert_eq!(last.content, "…");
+        assert!(lines.iter().all(|line| line.position.y() + 16 <= 40));
+        assert!(last.bounding_box().unwrap().top_left.x() + 1 < 100);
+    }
+
+    #[test]
+    fn clips_lines_wider_than_the_box() {
+        // Arrange
+        let area = Rectangle::new((0, 0).into(), 6, 40);
+        let text_box = TextBox::new(area, "W", 16).with_align(HorizontalAlign::Right);
+
+        // Act
+        let pixels: Vec<Pixel> = text_box.pixels().collect();
+
+        // Assert
+        let expected: Vec<Pixel> = Text::new((6, 0).into(), "W", 16)
+            .with_align(HorizontalAlign::Right, VerticalAlign::Top)
+            .pixels()
+            .filter(|p| area.contains(*p))
+            .collect();
+        assert!(!pixels.is_empty());
+        assert_eq!(pixels, expected);
+    }
+}